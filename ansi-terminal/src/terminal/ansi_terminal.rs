@@ -1,4 +1,5 @@
 use super::byte_prefix_tree::{BytePrefixTree, Found};
+use super::keyboard;
 use super::low_level::LowLevel;
 use super::term_info_cache::{MousePrefix, TermInfoCache, TerminalInput};
 use crate::error::Result;
@@ -227,26 +228,47 @@ impl AnsiTerminal {
         prefix_tree: &BytePrefixTree<TerminalInput>,
         slice: &[u8],
     ) -> Result<()> {
-        let (term_input, rest) = match prefix_tree.get_longest(slice) {
-            None => {
-                // slice does not begin with an escape sequence - chip off the start and try again
-                let s = if let Ok(s) = ::std::str::from_utf8(&slice) {
-                    s
-                } else {
-                    return Ok(());
-                };
-                if let Some((ch, rest)) = Self::chip_char(s) {
-                    (Some(TerminalInput::Char(ch)), Some(rest.as_bytes()))
-                } else {
-                    (None, None)
+        let (term_input, rest) = if let Some((input, rest)) = keyboard::parse_modified_csi(slice) {
+            (
+                Some(TerminalInput::Literal(Input::Keyboard(input))),
+                Some(rest),
+            )
+        } else {
+            match prefix_tree.get_longest(slice) {
+                None => {
+                    // slice does not begin with an escape sequence - chip off the start and try again
+                    let s = if let Ok(s) = ::std::str::from_utf8(&slice) {
+                        s
+                    } else {
+                        return Ok(());
+                    };
+                    match Self::chip_char(s) {
+                        // an escape followed by a character is the character with alt held,
+                        // unless the character is a control code such as another escape
+                        Some(('\u{1b}', rest)) => {
+                            match Self::chip_char(rest).and_then(|(ch, rest)| {
+                                keyboard::alt_char_input(ch).map(|input| (input, rest))
+                            }) {
+                                Some((input, rest)) => (
+                                    Some(TerminalInput::Literal(Input::Keyboard(input))),
+                                    Some(rest.as_bytes()),
+                                ),
+                                None => {
+                                    (Some(TerminalInput::Char('\u{1b}')), Some(rest.as_bytes()))
+                                }
+                            }
+                        }
+                        Some((ch, rest)) => (Some(TerminalInput::Char(ch)), Some(rest.as_bytes())),
+                        None => (None, None),
+                    }
                 }
+                Some(Found::Exact(input)) => (Some(*input), None),
+                Some(Found::WithRemaining(input, remaining)) => (Some(*input), Some(remaining)),
             }
-            Some(Found::Exact(input)) => (Some(*input), None),
-            Some(Found::WithRemaining(input, remaining)) => (Some(*input), Some(remaining)),
         };
         let rest = if let Some(input) = term_input {
             let (input, rest) = match input {
-                TerminalInput::Char(ch) => (Some(Input::Keyboard(KeyboardInput::Char(ch))), rest),
+                TerminalInput::Char(ch) => (Some(Input::Keyboard(keyboard::char_input(ch))), rest),
                 TerminalInput::Literal(input) => (Some(input), rest),
                 TerminalInput::MousePrefix(prefix) => {
                     if let Some(rest) = rest {
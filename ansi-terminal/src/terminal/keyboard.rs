@@ -0,0 +1,128 @@
+use chargrid_input::{keys, Key, KeyModifiers, KeyboardInput};
+
+const ESCAPE: u8 = 0x1b;
+
+/// Decodes a character sent by the terminal, treating control codes as ctrl held
+/// with the corresponding key. ETX is kept as `keys::ETX`, as that's how
+/// applications are asked to quit.
+pub fn char_input(ch: char) -> KeyboardInput {
+    match ch {
+        '\u{3}' => keys::ETX,
+        '\u{8}' | '\u{7f}' => keys::BACKSPACE,
+        '\u{9}' => keys::TAB,
+        '\u{d}' => keys::RETURN,
+        '\u{1b}' => keys::ESCAPE,
+        '\u{0}' => KeyboardInput::ctrl(Key::Char(' ')),
+        '\u{1}'..='\u{1a}' => KeyboardInput::ctrl(Key::Char((b'a' + ch as u8 - 1) as char)),
+        '\u{1c}'..='\u{1f}' => KeyboardInput::ctrl(Key::Char((b'\\' + ch as u8 - 0x1c) as char)),
+        _ => KeyboardInput::new(Key::Char(ch)),
+    }
+}
+
+/// Decodes a character preceded by an escape, which is how terminals send alt.
+/// Returns `None` for control codes other than backspace (notably another
+/// escape), in which case the escape was pressed on its own.
+pub fn alt_char_input(ch: char) -> Option<KeyboardInput> {
+    if ch.is_control() && ch != '\u{8}' && ch != '\u{7f}' {
+        return None;
+    }
+    let input = char_input(ch);
+    Some(input.with_modifiers(input.modifiers | KeyModifiers::ALT))
+}
+
+/// Converts an xterm modifier parameter, which is 1 plus a bitmask of the
+/// modifiers held
+fn modifiers_from_param(param: u32) -> KeyModifiers {
+    let bits = param.saturating_sub(1);
+    let mut modifiers = KeyModifiers::NONE;
+    if bits & 1 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if bits & 2 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if bits & 4 != 0 {
+        modifiers |= KeyModifiers::CTRL;
+    }
+    if bits & 8 != 0 {
+        modifiers |= KeyModifiers::SUPER;
+    }
+    modifiers
+}
+
+fn parse_number(slice: &[u8]) -> Option<(u32, &[u8])> {
+    let len = slice.iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = std::str::from_utf8(&slice[..len]).ok()?.parse().ok()?;
+    Some((number, &slice[len..]))
+}
+
+/// Parses an xterm-style escape sequence for a key with modifiers held, of the
+/// form `CSI <key>;<modifiers><final byte>` (e.g. `CSI 1;5A` for ctrl+up). Returns
+/// the input and the remaining bytes.
+pub fn parse_modified_csi(slice: &[u8]) -> Option<(KeyboardInput, &[u8])> {
+    let slice = slice.strip_prefix(&[ESCAPE, b'['])?;
+    let (number, slice) = parse_number(slice)?;
+    let slice = slice.strip_prefix(b";")?;
+    let (modifiers, slice) = parse_number(slice)?;
+    let (&final_byte, rest) = slice.split_first()?;
+    let key = match (final_byte, number) {
+        (b'A', 1) => Key::Up,
+        (b'B', 1) => Key::Down,
+        (b'C', 1) => Key::Right,
+        (b'D', 1) => Key::Left,
+        (b'H', 1) => Key::Home,
+        (b'F', 1) => Key::End,
+        (b'P', 1) => Key::Function(1),
+        (b'Q', 1) => Key::Function(2),
+        (b'R', 1) => Key::Function(3),
+        (b'S', 1) => Key::Function(4),
+        (b'~', 1) | (b'~', 7) => Key::Home,
+        (b'~', 3) => Key::Delete,
+        (b'~', 4) | (b'~', 8) => Key::End,
+        (b'~', 5) => Key::PageUp,
+        (b'~', 6) => Key::PageDown,
+        (b'~', n @ 11..=15) => Key::Function(n as u8 - 10),
+        (b'~', n @ 17..=21) => Key::Function(n as u8 - 11),
+        (b'~', n @ 23..=24) => Key::Function(n as u8 - 12),
+        _ => return None,
+    };
+    let input = KeyboardInput::new(key).with_modifiers(modifiers_from_param(modifiers));
+    Some((input, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_codes_and_modifier_parameters() {
+        assert_eq!(char_input('\u{3}'), keys::ETX);
+        assert_ne!(keys::ETX, KeyboardInput::ctrl(Key::Char('c')));
+        assert_eq!(char_input('\u{1a}'), KeyboardInput::ctrl(Key::Char('z')));
+        assert_eq!(char_input('\u{7f}'), keys::BACKSPACE);
+        assert_eq!(
+            alt_char_input('x'),
+            Some(KeyboardInput::alt(Key::Char('x')))
+        );
+        assert_eq!(
+            alt_char_input('\u{7f}'),
+            Some(keys::BACKSPACE.with_modifiers(KeyModifiers::ALT))
+        );
+        assert_eq!(alt_char_input('\u{1b}'), None);
+        assert_eq!(alt_char_input('\u{3}'), None);
+        assert_eq!(
+            parse_modified_csi(b"\x1b[1;5Ax"),
+            Some((KeyboardInput::ctrl(Key::Up), &b"x"[..]))
+        );
+        assert_eq!(
+            parse_modified_csi(b"\x1b[15;4~"),
+            Some((
+                KeyboardInput::new(Key::Function(5))
+                    .with_modifiers(KeyModifiers::SHIFT | KeyModifiers::ALT),
+                &b""[..]
+            ))
+        );
+        assert_eq!(parse_modified_csi(b"\x1b[A"), None);
+        assert_eq!(parse_modified_csi(b"\x1b[M ab"), None);
+    }
+}
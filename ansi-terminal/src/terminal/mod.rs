@@ -5,6 +5,7 @@ use chargrid_render::*;
 mod ansi_colour_codes;
 mod ansi_terminal;
 mod byte_prefix_tree;
+mod keyboard;
 mod low_level;
 mod term_info_cache;
 
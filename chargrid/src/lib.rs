@@ -1,8 +1,11 @@
 pub use chargrid_app as app;
 pub use chargrid_decorator as decorator;
+pub use chargrid_dialog as dialog;
+pub use chargrid_edit as edit;
 pub use chargrid_event_routine as event_routine;
 pub use chargrid_input as input;
 pub use chargrid_menu as menu;
 pub use chargrid_render as render;
+pub use chargrid_tabs as tabs;
 pub use chargrid_text as text;
 pub use render::{Coord, Size};
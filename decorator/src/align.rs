@@ -56,10 +56,12 @@ where
             AlignmentY::Centre => (context.size.y() as i32 - data_size.y() as i32) / 2,
             AlignmentY::Bottom => context.size.y() as i32 - data_size.y() as i32,
         };
-        self.view.view(
-            data,
-            context.add_offset(Coord::new(x_offset, y_offset)),
-            frame,
-        );
+        // the view is drawn with the size of context it was measured in, so views
+        // whose size depends on the space available lay out the same way again
+        let aligned_context = ViewContext {
+            offset: context.offset + Coord::new(x_offset, y_offset),
+            ..context
+        };
+        self.view.view(data, aligned_context, frame);
     }
 }
@@ -0,0 +1,122 @@
+use chargrid_render::*;
+
+/// Gives a view the largest space with the given ratio of width to height which
+/// fits in the space available to it. The ratio is measured in cells, so a ratio
+/// of 2:1 will look roughly square in most terminal fonts.
+pub struct AspectRatioView<V> {
+    pub view: V,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl<V> AspectRatioView<V> {
+    fn fit(&self, size: Size) -> Size {
+        if self.width == 0 || self.height == 0 {
+            return Size::new(0, 0);
+        }
+        let width = size.width() as u64;
+        let height = size.height() as u64;
+        let height_for_width = (width * self.height as u64) / self.width as u64;
+        if height_for_width <= height {
+            Size::new(width as u32, height_for_width as u32)
+        } else {
+            let width_for_height = (height * self.width as u64) / self.height as u64;
+            Size::new(width_for_height as u32, height as u32)
+        }
+    }
+}
+
+impl<V, T> View<T> for AspectRatioView<V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        let size = self.fit(context.size);
+        self.view.view(data, context.constrain_size_to(size), frame);
+    }
+
+    fn size<C: ColModify>(&mut self, _: T, context: ViewContext<C>) -> Size {
+        self.fit(context.size)
+    }
+
+    fn view_size<F: Frame, C: ColModify>(
+        &mut self,
+        data: T,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) -> Size {
+        self.view(data, context, frame);
+        self.fit(context.size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AlignView, Alignment, BorderStyle, BorderView};
+    use chargrid_test_grid::TestGrid;
+
+    /// Fills whatever space it's given
+    struct Fill;
+
+    impl View<()> for Fill {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            for y in 0..context.size.height() as i32 {
+                for x in 0..context.size.width() as i32 {
+                    let view_cell = ViewCell::new().with_character('#');
+                    frame.set_cell_relative(Coord::new(x, y), 0, view_cell, context);
+                }
+            }
+        }
+    }
+
+    fn two_by_one() -> AspectRatioView<Fill> {
+        AspectRatioView {
+            view: Fill,
+            width: 2,
+            height: 1,
+        }
+    }
+
+    #[test]
+    fn ratio_within_align_and_border() {
+        let size = Size::new(8, 6);
+        let context = ViewContext::default_with_size(size);
+        let mut test_grid = TestGrid::new(size);
+        assert_eq!(
+            two_by_one().view_size((), context, &mut test_grid),
+            Size::new(8, 4)
+        );
+        let mut test_grid = TestGrid::new(size);
+        let mut aligned = AlignView {
+            view: two_by_one(),
+            alignment: Alignment::centre(),
+        };
+        aligned.view((), context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["        ", "########", "########", "########", "########", "        "]
+        );
+        let mut test_grid = TestGrid::new(size);
+        let style = BorderStyle::new();
+        let mut bordered = BorderView {
+            view: two_by_one(),
+            style: &style,
+        };
+        assert_eq!(
+            bordered.view_size((), context, &mut test_grid),
+            Size::new(7, 4)
+        );
+        assert_eq!(
+            test_grid.string_rows(),
+            &[
+                "┌─────┐ ",
+                "│#####│ ",
+                "│#####│ ",
+                "└─────┘ ",
+                "        ",
+                "        "
+            ]
+        );
+    }
+}
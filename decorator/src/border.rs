@@ -1,6 +1,8 @@
+use crate::align::AlignmentX;
 use chargrid_render::*;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthChar;
 
 /// The characters comprising a border. By default, borders are made of unicode
 /// box-drawing characters, but they can be changed to arbitrary characters via
@@ -41,6 +43,66 @@ impl BorderChars {
             after_title: '├',
         }
     }
+    pub fn double() -> Self {
+        Self {
+            top: '═',
+            bottom: '═',
+            left: '║',
+            right: '║',
+            top_left: '╔',
+            top_right: '╗',
+            bottom_left: '╚',
+            bottom_right: '╝',
+            before_title: '╡',
+            after_title: '╞',
+        }
+    }
+    pub fn rounded() -> Self {
+        Self {
+            top_left: '╭',
+            top_right: '╮',
+            bottom_left: '╰',
+            bottom_right: '╯',
+            ..Self::single()
+        }
+    }
+    pub fn heavy() -> Self {
+        Self {
+            top: '━',
+            bottom: '━',
+            left: '┃',
+            right: '┃',
+            top_left: '┏',
+            top_right: '┓',
+            bottom_left: '┗',
+            bottom_right: '┛',
+            before_title: '┫',
+            after_title: '┣',
+        }
+    }
+    pub fn dashed() -> Self {
+        Self {
+            top: '╌',
+            bottom: '╌',
+            left: '╎',
+            right: '╎',
+            ..Self::single()
+        }
+    }
+    pub fn ascii() -> Self {
+        Self {
+            top: '-',
+            bottom: '-',
+            left: '|',
+            right: '|',
+            top_left: '+',
+            top_right: '+',
+            bottom_left: '+',
+            bottom_right: '+',
+            before_title: '[',
+            after_title: ']',
+        }
+    }
 }
 
 /// The space in cells between the edge of the bordered area
@@ -65,19 +127,115 @@ impl BorderPadding {
     }
 }
 
+/// Which sides of a border are drawn. No space is reserved for a side which
+/// is not drawn.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct BorderSides {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl Default for BorderSides {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl BorderSides {
+    pub fn all() -> Self {
+        Self {
+            top: true,
+            bottom: true,
+            left: true,
+            right: true,
+        }
+    }
+    pub fn none() -> Self {
+        Self {
+            top: false,
+            bottom: false,
+            left: false,
+            right: false,
+        }
+    }
+    pub fn horizontal() -> Self {
+        Self {
+            top: true,
+            bottom: true,
+            ..Self::none()
+        }
+    }
+    pub fn vertical() -> Self {
+        Self {
+            left: true,
+            right: true,
+            ..Self::none()
+        }
+    }
+}
+
+/// The horizontal edge of a border on which a title is drawn.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorderEdge {
+    Top,
+    Bottom,
+}
+
+/// Where along the border a title is drawn. A title on an edge which is
+/// disabled by `BorderSides` is not drawn.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct BorderTitlePosition {
+    pub edge: BorderEdge,
+    pub alignment: AlignmentX,
+}
+
+impl BorderTitlePosition {
+    pub fn new(edge: BorderEdge, alignment: AlignmentX) -> Self {
+        Self { edge, alignment }
+    }
+    pub fn top_left() -> Self {
+        Self::new(BorderEdge::Top, AlignmentX::Left)
+    }
+    pub fn bottom_right() -> Self {
+        Self::new(BorderEdge::Bottom, AlignmentX::Right)
+    }
+}
+
 /// Decorate another element with a border.
 /// It's possible to give the border a title, in which case
-/// the text appears in the top-left corner.
+/// the text appears in the top-left corner by default, and a footer,
+/// which appears in the bottom-right corner by default.
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct BorderStyle {
     pub title: Option<String>,
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default = "BorderTitlePosition::top_left")
+    )]
+    pub title_position: BorderTitlePosition,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub footer: Option<String>,
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default = "BorderTitlePosition::bottom_right")
+    )]
+    pub footer_position: BorderTitlePosition,
     pub padding: BorderPadding,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub sides: BorderSides,
     pub chars: BorderChars,
     pub foreground: Rgb24,
     pub background: Option<Rgb24>,
     pub bold: bool,
     pub title_style: Style,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub footer_style: Style,
 }
 
 impl Default for BorderStyle {
@@ -90,12 +248,17 @@ impl BorderStyle {
     pub fn new() -> Self {
         Self {
             title: None,
+            title_position: BorderTitlePosition::top_left(),
+            footer: None,
+            footer_position: BorderTitlePosition::bottom_right(),
             padding: Default::default(),
+            sides: Default::default(),
             chars: Default::default(),
             foreground: Rgb24::new(255, 255, 255),
             background: None,
             bold: false,
             title_style: Style::new(),
+            footer_style: Style::new(),
         }
     }
 
@@ -105,24 +268,30 @@ impl BorderStyle {
             ..Self::new()
         }
     }
+    fn left_width(&self) -> u32 {
+        self.sides.left as u32
+    }
+    fn right_width(&self) -> u32 {
+        self.sides.right as u32
+    }
+    fn top_height(&self) -> u32 {
+        self.sides.top as u32
+    }
+    fn bottom_height(&self) -> u32 {
+        self.sides.bottom as u32
+    }
     fn child_offset(&self) -> Coord {
         Coord {
-            x: (self.padding.left + 1) as i32,
-            y: (self.padding.top + 1) as i32,
+            x: (self.padding.left + self.left_width()) as i32,
+            y: (self.padding.top + self.top_height()) as i32,
         }
     }
     fn child_constrain_size_by(&self) -> Size {
         Size::new(
-            self.padding.left + self.padding.right + 2,
-            self.padding.top + self.padding.bottom + 2,
+            self.padding.left + self.padding.right + self.left_width() + self.right_width(),
+            self.padding.top + self.padding.bottom + self.top_height() + self.bottom_height(),
         )
     }
-    fn span_offset(&self) -> Coord {
-        Coord {
-            x: (self.padding.left + self.padding.right + 1) as i32,
-            y: (self.padding.top + self.padding.bottom + 1) as i32,
-        }
-    }
     fn view_cell(&self, character: char) -> ViewCell {
         ViewCell {
             character: Some(character),
@@ -136,92 +305,213 @@ impl BorderStyle {
     }
 }
 
-fn draw_border<F, C>(style: &BorderStyle, size: Size, context: ViewContext<C>, frame: &mut F)
+/// The number of cells taken up by a title, not including the characters
+/// drawn before and after it. Characters which don't take up any space on
+/// their own (such as combining characters) are skipped when drawing, so
+/// they don't contribute to the width.
+fn title_width(title: &str) -> i32 {
+    title.chars().map(|ch| ch.width().unwrap_or(0) as i32).sum()
+}
+
+/// The part of a row of the border in which a title may be drawn.
+#[derive(Debug, Clone, Copy)]
+struct TitleRow {
+    y: i32,
+    start: i32,
+    end: i32,
+}
+
+/// The characters of `title` which fit in `max_width` cells. Characters which
+/// don't take up any space are skipped. If the title doesn't fit, it's cut short
+/// and ends with an ellipsis.
+fn fit_title(title: &str, max_width: i32) -> Vec<char> {
+    let visible = title.chars().filter(|&ch| ch.width().unwrap_or(0) > 0);
+    if title_width(title) <= max_width {
+        return visible.collect();
+    }
+    let mut chars = Vec::new();
+    let mut width = 0;
+    for ch in visible {
+        let ch_width = ch.width().unwrap_or(0) as i32;
+        if width + ch_width > max_width - 1 {
+            break;
+        }
+        chars.push(ch);
+        width += ch_width;
+    }
+    if max_width > 0 {
+        chars.push('…');
+    }
+    chars
+}
+
+/// Draws `title` positioned with `alignment` within `row`, truncating it if
+/// it doesn't fit. Returns the range of x coordinates covered by the title.
+fn draw_title<F, C>(
+    style: &BorderStyle,
+    title: &str,
+    title_style: Style,
+    alignment: AlignmentX,
+    row: TitleRow,
+    context: ViewContext<C>,
+    frame: &mut F,
+) -> (i32, i32)
 where
     C: ColModify,
     F: Frame,
 {
-    let span = style.span_offset() + size;
-    frame.set_cell_relative(
-        Coord::new(0, 0),
-        0,
-        style.view_cell(style.chars.top_left),
-        context,
-    );
-    frame.set_cell_relative(
-        Coord::new(span.x, 0),
-        0,
-        style.view_cell(style.chars.top_right),
-        context,
-    );
-    frame.set_cell_relative(
-        Coord::new(0, span.y),
-        0,
-        style.view_cell(style.chars.bottom_left),
-        context,
-    );
+    let TitleRow { y, start, end } = row;
+    if end - start < 2 {
+        return (start, start);
+    }
+    let title = fit_title(title, end - start - 2);
+    let width = title
+        .iter()
+        .map(|ch| ch.width().unwrap_or(0) as i32)
+        .sum::<i32>()
+        + 2;
+    let title_start = match alignment {
+        AlignmentX::Left => start,
+        AlignmentX::Centre => start + (end - start - width) / 2,
+        AlignmentX::Right => end - width,
+    }
+    .max(start);
     frame.set_cell_relative(
-        Coord::new(span.x, span.y),
+        Coord::new(title_start, y),
         0,
-        style.view_cell(style.chars.bottom_right),
+        style.view_cell(style.chars.before_title),
         context,
     );
-    let title_offset = if let Some(title) = style.title.as_ref() {
-        let before = Coord::new(1, 0);
-        let after = Coord::new(title.len() as i32 + 2, 0);
+    let mut x = title_start + 1;
+    for ch in title {
+        let ch_width = ch.width().unwrap_or(0) as i32;
         frame.set_cell_relative(
-            before,
+            Coord::new(x, y),
             0,
-            style.view_cell(style.chars.before_title),
+            ViewCell {
+                style: title_style,
+                character: Some(ch),
+            },
             context,
         );
-        frame.set_cell_relative(after, 0, style.view_cell(style.chars.after_title), context);
-        for (index, ch) in title.chars().enumerate() {
-            let coord = Coord::new(index as i32 + 2, 0);
-            frame.set_cell_relative(
-                coord,
-                0,
-                ViewCell {
-                    style: style.title_style,
-                    character: Some(ch),
-                },
-                context,
-            );
-        }
-        title.len() as i32 + 2
+        x += ch_width;
+    }
+    frame.set_cell_relative(
+        Coord::new(x, y),
+        0,
+        style.view_cell(style.chars.after_title),
+        context,
+    );
+    (title_start, x + 1)
+}
+
+fn draw_horizontal_edge<F, C>(
+    style: &BorderStyle,
+    edge: BorderEdge,
+    y: i32,
+    right_x: i32,
+    context: ViewContext<C>,
+    frame: &mut F,
+) where
+    C: ColModify,
+    F: Frame,
+{
+    let (left_char, right_char, edge_char) = match edge {
+        BorderEdge::Top => (style.chars.top_left, style.chars.top_right, style.chars.top),
+        BorderEdge::Bottom => (
+            style.chars.bottom_left,
+            style.chars.bottom_right,
+            style.chars.bottom,
+        ),
+    };
+    let start = if style.sides.left {
+        frame.set_cell_relative(Coord::new(0, y), 0, style.view_cell(left_char), context);
+        1
     } else {
         0
     };
-    for i in (1 + title_offset)..span.x {
+    let end = if style.sides.right {
         frame.set_cell_relative(
-            Coord::new(i, 0),
+            Coord::new(right_x, y),
             0,
-            style.view_cell(style.chars.top),
+            style.view_cell(right_char),
             context,
         );
+        right_x
+    } else {
+        right_x + 1
+    };
+    let titles = [
+        (&style.title, style.title_position, style.title_style),
+        (&style.footer, style.footer_position, style.footer_style),
+    ];
+    let mut covered = Vec::new();
+    for (title, position, title_style) in titles.iter() {
+        if let Some(title) = title.as_ref() {
+            if position.edge == edge {
+                covered.push(draw_title(
+                    style,
+                    title,
+                    *title_style,
+                    position.alignment,
+                    TitleRow { y, start, end },
+                    context,
+                    frame,
+                ));
+            }
+        }
     }
-    for i in 1..span.x {
-        frame.set_cell_relative(
-            Coord::new(i, span.y),
-            0,
-            style.view_cell(style.chars.bottom),
-            context,
-        );
+    for x in start..end {
+        if covered
+            .iter()
+            .all(|&(title_start, title_end)| x < title_start || x >= title_end)
+        {
+            frame.set_cell_relative(Coord::new(x, y), 0, style.view_cell(edge_char), context);
+        }
     }
-    for i in 1..span.y {
-        frame.set_cell_relative(
-            Coord::new(0, i),
-            0,
-            style.view_cell(style.chars.left),
-            context,
-        );
-        frame.set_cell_relative(
-            Coord::new(span.x, i),
-            0,
-            style.view_cell(style.chars.right),
+}
+
+fn draw_border<F, C>(style: &BorderStyle, size: Size, context: ViewContext<C>, frame: &mut F)
+where
+    C: ColModify,
+    F: Frame,
+{
+    let right_x =
+        (style.left_width() + style.padding.left + size.width() + style.padding.right) as i32;
+    let bottom_y =
+        (style.top_height() + style.padding.top + size.height() + style.padding.bottom) as i32;
+    let right_x_inclusive = right_x + style.right_width() as i32 - 1;
+    if style.sides.top {
+        draw_horizontal_edge(style, BorderEdge::Top, 0, right_x_inclusive, context, frame);
+    }
+    if style.sides.bottom {
+        draw_horizontal_edge(
+            style,
+            BorderEdge::Bottom,
+            bottom_y,
+            right_x_inclusive,
             context,
+            frame,
         );
     }
+    for i in (style.top_height() as i32)..bottom_y {
+        if style.sides.left {
+            frame.set_cell_relative(
+                Coord::new(0, i),
+                0,
+                style.view_cell(style.chars.left),
+                context,
+            );
+        }
+        if style.sides.right {
+            frame.set_cell_relative(
+                Coord::new(right_x, i),
+                0,
+                style.view_cell(style.chars.right),
+                context,
+            );
+        }
+    }
 }
 
 fn border_view<V, T, F, C>(
@@ -255,3 +545,101 @@ where
         border_view(&mut self.view, data, self.style, context, frame);
     }
 }
+
+/// A `BorderView` which owns its style
+pub struct BorderViewOwned<V> {
+    pub view: V,
+    pub style: BorderStyle,
+}
+
+impl<V, T> View<T> for BorderViewOwned<V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        border_view(&mut self.view, data, &self.style, context, frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    struct Blank(Size);
+
+    impl View<()> for Blank {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            for y in 0..self.0.height() as i32 {
+                for x in 0..self.0.width() as i32 {
+                    frame.set_cell_relative(
+                        Coord::new(x, y),
+                        0,
+                        ViewCell::new().with_character('.'),
+                        context,
+                    );
+                }
+            }
+        }
+    }
+
+    fn render(style: &BorderStyle, child_size: Size, grid_size: Size) -> Vec<String> {
+        let mut test_grid = TestGrid::new(grid_size);
+        let context = ViewContext::default_with_size(grid_size);
+        BorderView {
+            view: Blank(child_size),
+            style,
+        }
+        .view((), context, &mut test_grid);
+        test_grid.string_rows()
+    }
+
+    #[test]
+    fn title_and_footer_alignment() {
+        let style = BorderStyle {
+            title_position: BorderTitlePosition::new(BorderEdge::Top, AlignmentX::Centre),
+            footer: Some("é☃".to_string()),
+            ..BorderStyle::new_with_title("ab")
+        };
+        assert_eq!(
+            render(&style, Size::new(8, 1), Size::new(11, 4)),
+            &["┌──┤ab├──┐ ", "│........│ ", "└────┤é☃├┘ ", "           ",]
+        );
+    }
+
+    #[test]
+    fn wide_title_characters() {
+        let style = BorderStyle {
+            chars: BorderChars::ascii(),
+            ..BorderStyle::new_with_title("日本")
+        };
+        assert_eq!(
+            render(&style, Size::new(8, 1), Size::new(11, 4)),
+            &["+[日 本 ]--+ ", "|........| ", "+--------+ ", "           ",]
+        );
+    }
+
+    #[test]
+    fn partial_border() {
+        let style = BorderStyle {
+            sides: BorderSides::horizontal(),
+            ..BorderStyle::new_with_title("x")
+        };
+        assert_eq!(
+            render(&style, Size::new(5, 1), Size::new(5, 4)),
+            &["┤x├──", ".....", "─────", "     "]
+        );
+    }
+
+    #[test]
+    fn long_title_is_truncated() {
+        let style = BorderStyle {
+            footer: Some("日本語".to_string()),
+            ..BorderStyle::new_with_title("a long title")
+        };
+        assert_eq!(
+            render(&style, Size::new(5, 1), Size::new(9, 4)),
+            &["┌┤a …├┐  ", "│.....│  ", "└┤日 …├┘  ", "         "]
+        );
+    }
+}
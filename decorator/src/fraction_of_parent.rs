@@ -0,0 +1,117 @@
+use chargrid_render::*;
+
+/// Gives a view a fraction of the space available to it, where 1.0 is all the
+/// available space. The decorated view is treated as occupying the entire
+/// fraction, so it can be aligned or bordered as a block.
+pub struct FractionOfParentView<V> {
+    pub view: V,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl<V> FractionOfParentView<V> {
+    fn fraction_of(&self, size: Size) -> Size {
+        let scale = |length: u32, fraction: f64| {
+            ((length as f64 * fraction.clamp(0., 1.)).round() as u32).min(length)
+        };
+        Size::new(
+            scale(size.width(), self.width),
+            scale(size.height(), self.height),
+        )
+    }
+}
+
+impl<V, T> View<T> for FractionOfParentView<V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        let size = self.fraction_of(context.size);
+        self.view.view(data, context.constrain_size_to(size), frame);
+    }
+
+    fn size<C: ColModify>(&mut self, _: T, context: ViewContext<C>) -> Size {
+        self.fraction_of(context.size)
+    }
+
+    fn view_size<F: Frame, C: ColModify>(
+        &mut self,
+        data: T,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) -> Size {
+        self.view(data, context, frame);
+        self.fraction_of(context.size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AlignView, Alignment, BorderStyle, BorderView};
+    use chargrid_test_grid::TestGrid;
+
+    /// Fills whatever space it's given
+    struct Fill;
+
+    impl View<()> for Fill {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            for y in 0..context.size.height() as i32 {
+                for x in 0..context.size.width() as i32 {
+                    let view_cell = ViewCell::new().with_character('#');
+                    frame.set_cell_relative(Coord::new(x, y), 0, view_cell, context);
+                }
+            }
+        }
+    }
+
+    fn half() -> FractionOfParentView<Fill> {
+        FractionOfParentView {
+            view: Fill,
+            width: 0.5,
+            height: 0.5,
+        }
+    }
+
+    #[test]
+    fn fraction_within_align_and_border() {
+        let size = Size::new(8, 6);
+        let context = ViewContext::default_with_size(size);
+        let mut test_grid = TestGrid::new(size);
+        assert_eq!(
+            half().view_size((), context, &mut test_grid),
+            Size::new(4, 3)
+        );
+        let mut test_grid = TestGrid::new(size);
+        let mut aligned = AlignView {
+            view: half(),
+            alignment: Alignment::centre(),
+        };
+        aligned.view((), context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["        ", "  ####  ", "  ####  ", "  ####  ", "        ", "        "]
+        );
+        let mut test_grid = TestGrid::new(size);
+        let style = BorderStyle::new();
+        let mut bordered = BorderView {
+            view: half(),
+            style: &style,
+        };
+        assert_eq!(
+            bordered.view_size((), context, &mut test_grid),
+            Size::new(5, 4)
+        );
+        assert_eq!(
+            test_grid.string_rows(),
+            &[
+                "┌───┐   ",
+                "│###│   ",
+                "│###│   ",
+                "└───┘   ",
+                "        ",
+                "        "
+            ]
+        );
+    }
+}
@@ -1,17 +1,33 @@
 mod align;
+mod aspect_ratio;
 mod border;
 mod bound;
 mod col_modify;
 mod fill_background;
+mod fraction_of_parent;
+mod max_size;
 mod min_size;
 mod pad;
+mod popup;
+mod shadow;
+mod split;
+mod stack;
 mod vertical_scroll;
+mod view_ext;
 
 pub use align::*;
+pub use aspect_ratio::*;
 pub use border::*;
 pub use bound::*;
 pub use col_modify::*;
 pub use fill_background::*;
+pub use fraction_of_parent::*;
+pub use max_size::*;
 pub use min_size::*;
 pub use pad::*;
+pub use popup::*;
+pub use shadow::*;
+pub use split::*;
+pub use stack::*;
 pub use vertical_scroll::*;
+pub use view_ext::*;
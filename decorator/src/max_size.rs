@@ -0,0 +1,98 @@
+use chargrid_render::*;
+
+/// Limits the space available to a view, without reserving space if the view
+/// is smaller than the limit.
+pub struct MaxSizeView<V> {
+    pub view: V,
+    pub size: Size,
+}
+
+impl<V, T> View<T> for MaxSizeView<V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        self.view
+            .view(data, context.constrain_size_to(self.size), frame);
+    }
+
+    fn size<C: ColModify>(&mut self, data: T, context: ViewContext<C>) -> Size {
+        self.view
+            .size(data, context.constrain_size_to(self.size))
+            .pairwise_min(self.size)
+    }
+
+    fn view_size<F: Frame, C: ColModify>(
+        &mut self,
+        data: T,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) -> Size {
+        self.view
+            .view_size(data, context.constrain_size_to(self.size), frame)
+            .pairwise_min(self.size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AlignView, Alignment, BorderStyle, BorderView};
+    use chargrid_test_grid::TestGrid;
+
+    /// Fills whatever space it's given
+    struct Fill;
+
+    impl View<()> for Fill {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            for y in 0..context.size.height() as i32 {
+                for x in 0..context.size.width() as i32 {
+                    let view_cell = ViewCell::new().with_character('#');
+                    frame.set_cell_relative(Coord::new(x, y), 0, view_cell, context);
+                }
+            }
+        }
+    }
+
+    fn max_size() -> MaxSizeView<Fill> {
+        MaxSizeView {
+            view: Fill,
+            size: Size::new(3, 2),
+        }
+    }
+
+    #[test]
+    fn limits_size_within_align_and_border() {
+        let size = Size::new(8, 5);
+        let context = ViewContext::default_with_size(size);
+        let mut test_grid = TestGrid::new(size);
+        assert_eq!(
+            max_size().view_size((), context, &mut test_grid),
+            Size::new(3, 2)
+        );
+        let mut test_grid = TestGrid::new(size);
+        let mut aligned = AlignView {
+            view: max_size(),
+            alignment: Alignment::centre(),
+        };
+        aligned.view((), context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["        ", "  ###   ", "  ###   ", "        ", "        "]
+        );
+        let mut test_grid = TestGrid::new(size);
+        let style = BorderStyle::new();
+        let mut bordered = BorderView {
+            view: max_size(),
+            style: &style,
+        };
+        assert_eq!(
+            bordered.view_size((), context, &mut test_grid),
+            Size::new(5, 4)
+        );
+        assert_eq!(
+            test_grid.string_rows(),
+            &["┌───┐   ", "│###│   ", "│###│   ", "└───┘   ", "        "]
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use chargrid_input::{Input, MouseInput};
+use chargrid_render::grid_2d::coord_2d::Axis;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The side of its anchor on which a popup is preferably placed
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopupSide {
+    Above,
+    Below,
+    Left,
+    Right,
+}
+
+impl PopupSide {
+    fn axis(self) -> Axis {
+        match self {
+            PopupSide::Above | PopupSide::Below => Axis::Y,
+            PopupSide::Left | PopupSide::Right => Axis::X,
+        }
+    }
+    fn is_after(self) -> bool {
+        match self {
+            PopupSide::Below | PopupSide::Right => true,
+            PopupSide::Above | PopupSide::Left => false,
+        }
+    }
+}
+
+/// Work out where to place a popup of a given size next to an anchor rectangle,
+/// such that it stays within `bounds`. The popup goes on the preferred side if it
+/// fits there, otherwise on the opposite side if it fits there, otherwise on
+/// whichever side has the most space. It is aligned with the start of the anchor,
+/// and shifted along the anchor to avoid overflowing the bounds.
+pub fn popup_offset(
+    side: PopupSide,
+    anchor_offset: Coord,
+    anchor_size: Size,
+    popup_size: Size,
+    bounds: Size,
+) -> Coord {
+    let axis = side.axis();
+    let length = popup_size.get(axis) as i32;
+    let space_before = anchor_offset.get(axis);
+    let after_start = anchor_offset.get(axis) + anchor_size.get(axis) as i32;
+    let space_after = bounds.get(axis) as i32 - after_start;
+    let fits_before = length <= space_before;
+    let fits_after = length <= space_after;
+    let place_after = match (side.is_after(), fits_before, fits_after) {
+        (true, _, true) => true,
+        (false, true, _) => false,
+        (true, true, false) => false,
+        (false, false, true) => true,
+        (_, false, false) => space_after >= space_before,
+    };
+    let main = if place_after {
+        after_start
+    } else {
+        (space_before - length).max(0)
+    };
+    let cross_axis = axis.other();
+    let cross = anchor_offset
+        .get(cross_axis)
+        .min(bounds.get(cross_axis) as i32 - popup_size.get(cross_axis) as i32)
+        .max(0);
+    axis.new_coord(main, cross)
+}
+
+/// Draws a view next to an anchor rectangle, avoiding the edges of the context.
+/// The anchor is relative to the context, so the root context should be passed
+/// to prevent the popup overflowing the screen. The popup is drawn `depth` layers
+/// above the context.
+pub struct PopupView<V> {
+    pub view: V,
+    pub anchor_offset: Coord,
+    pub anchor_size: Size,
+    pub side: PopupSide,
+    pub depth: i8,
+}
+
+impl<V> PopupView<V> {
+    pub fn new(view: V, anchor_offset: Coord, anchor_size: Size) -> Self {
+        Self {
+            view,
+            anchor_offset,
+            anchor_size,
+            side: PopupSide::Below,
+            depth: 16,
+        }
+    }
+}
+
+impl<V, T> View<T> for PopupView<V>
+where
+    V: View<T>,
+    T: Clone,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        let popup_size = self.view.size(data.clone(), context);
+        let offset = popup_offset(
+            self.side,
+            self.anchor_offset,
+            self.anchor_size,
+            popup_size,
+            context.size,
+        );
+        self.view.view(
+            data,
+            context.add_offset(offset).add_depth(self.depth),
+            frame,
+        );
+    }
+}
+
+/// Tracks the mouse so a tooltip can be shown once it has hovered over the same
+/// cell for a while. Pass it all input, and the durations of `CommonEvent::Frame`
+/// events.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TooltipState {
+    delay: Duration,
+    hover_coord: Option<Coord>,
+    hover_duration: Duration,
+}
+
+impl TooltipState {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            hover_coord: None,
+            hover_duration: Duration::from_millis(0),
+        }
+    }
+    pub fn on_input(&mut self, input: Input) {
+        match input {
+            Input::Mouse(MouseInput::MouseMove {
+                button: None,
+                coord,
+            }) => {
+                if self.hover_coord != Some(coord) {
+                    self.hover_coord = Some(coord);
+                    self.hover_duration = Duration::from_millis(0);
+                }
+            }
+            _ => self.hide(),
+        }
+    }
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        if self.hover_coord.is_some() {
+            self.hover_duration += since_last_frame;
+        }
+    }
+    /// Hide the tooltip until the mouse moves again
+    pub fn hide(&mut self) {
+        self.hover_coord = None;
+        self.hover_duration = Duration::from_millis(0);
+    }
+    /// The screen coordinate of the cell a tooltip should be shown for, if any
+    pub fn coord(&self) -> Option<Coord> {
+        self.hover_coord
+            .filter(|_| self.hover_duration >= self.delay)
+    }
+}
+
+impl Default for TooltipState {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(500))
+    }
+}
+
+/// Draws a view as a popup below the hovered cell, once the mouse has hovered for
+/// long enough. Nothing is drawn otherwise. The data can be chosen based on
+/// `TooltipState::coord`.
+pub struct TooltipView<'t, V> {
+    pub view: V,
+    pub state: &'t TooltipState,
+}
+
+impl<'t, V, T> View<T> for TooltipView<'t, V>
+where
+    V: View<T>,
+    T: Clone,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        if let Some(coord) = self.state.coord() {
+            PopupView::new(&mut self.view, coord - context.offset, Size::new(1, 1))
+                .view(data, context, frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flip_and_shift() {
+        let bounds = Size::new(20, 10);
+        let popup_size = Size::new(6, 3);
+        let anchor_size = Size::new(1, 1);
+        assert_eq!(
+            popup_offset(
+                PopupSide::Below,
+                Coord::new(2, 2),
+                anchor_size,
+                popup_size,
+                bounds
+            ),
+            Coord::new(2, 3)
+        );
+        assert_eq!(
+            popup_offset(
+                PopupSide::Below,
+                Coord::new(17, 8),
+                anchor_size,
+                popup_size,
+                bounds
+            ),
+            Coord::new(14, 5)
+        );
+        assert_eq!(
+            popup_offset(
+                PopupSide::Left,
+                Coord::new(3, 9),
+                anchor_size,
+                popup_size,
+                bounds
+            ),
+            Coord::new(4, 7)
+        );
+    }
+}
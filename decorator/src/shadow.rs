@@ -0,0 +1,115 @@
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The appearance of a drop shadow. The shadow covers the area taken up by the
+/// decorated view, moved by `offset`, and darkens the cells beneath it by blending
+/// `rgb24` into their foreground and background colours with opacity `alpha`.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct ShadowStyle {
+    pub offset: Coord,
+    pub rgb24: Rgb24,
+    pub alpha: u8,
+}
+
+impl ShadowStyle {
+    pub fn new() -> Self {
+        Self {
+            offset: Coord::new(1, 1),
+            rgb24: Rgb24::new_grey(0),
+            alpha: 127,
+        }
+    }
+}
+
+impl Default for ShadowStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decorate another element with a drop shadow. Since the shadow is blended with
+/// whatever is already in the frame, the content it falls on must be drawn first.
+pub struct ShadowView<V> {
+    pub view: V,
+    pub style: ShadowStyle,
+}
+
+impl<V, T> View<T> for ShadowView<V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        let size = self.view.view_size(data, context.add_depth(1), frame);
+        let width = size.width() as i32;
+        let height = size.height() as i32;
+        let offset = self.style.offset;
+        for y in offset.y..(offset.y + height) {
+            for x in offset.x..(offset.x + width) {
+                if x >= 0 && x < width && y >= 0 && y < height {
+                    continue;
+                }
+                let coord = Coord::new(x, y);
+                frame.blend_cell_background_relative(
+                    coord,
+                    0,
+                    self.style.rgb24,
+                    self.style.alpha,
+                    blend_mode::LinearInterpolate,
+                    context,
+                );
+                frame.blend_cell_foreground_relative(
+                    coord,
+                    0,
+                    self.style.rgb24,
+                    self.style.alpha,
+                    blend_mode::LinearInterpolate,
+                    context,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Fill(Size);
+
+    impl View<()> for Fill {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            let style = Style::new()
+                .with_foreground(Rgb24::new_grey(255))
+                .with_background(Rgb24::new_grey(255));
+            let view_cell = ViewCell::new().with_character('#').with_style(style);
+            for coord in self.0.coord_iter_row_major() {
+                frame.set_cell_relative(coord, 0, view_cell, context);
+            }
+        }
+    }
+
+    #[test]
+    fn darkens_cells_beneath_offset_area() {
+        let size = Size::new(4, 3);
+        let mut buffer = Buffer::new(size);
+        let context = ViewContext::default_with_size(size);
+        Fill(size).view((), context, &mut buffer);
+        ShadowView {
+            view: Fill(Size::new(2, 1)),
+            style: ShadowStyle::new(),
+        }
+        .view((), context, &mut buffer);
+        let white = Rgb24::new_grey(255);
+        let shadow = white.linear_interpolate(Rgb24::new_grey(0), 127);
+        let cells = buffer.rows().collect::<Vec<_>>();
+        for &(x, y) in &[(0, 0), (1, 0), (2, 0), (0, 1), (3, 1), (1, 2)] {
+            assert_eq!(cells[y][x].background_colour, white, "({}, {})", x, y);
+        }
+        for &(x, y) in &[(1, 1), (2, 1)] {
+            assert_eq!(cells[y][x].background_colour, shadow, "({}, {})", x, y);
+            assert_eq!(cells[y][x].foreground_colour, shadow, "({}, {})", x, y);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use chargrid_input::{Input, Key, KeyboardInput, MouseButton, MouseInput};
+use chargrid_render::grid_2d::coord_2d::Axis;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The direction in which the panes of a split are laid out. In a horizontal
+/// split the first pane is to the left of the second, separated by a vertical
+/// divider. In a vertical split the first pane is above the second.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl SplitDirection {
+    fn axis(self) -> Axis {
+        match self {
+            SplitDirection::Horizontal => Axis::X,
+            SplitDirection::Vertical => Axis::Y,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct SplitStyle {
+    pub direction: SplitDirection,
+    pub divider: char,
+    pub divider_style: Style,
+    /// The minimum length of the first pane along the direction of the split
+    pub min_first: u32,
+    /// The minimum length of the second pane along the direction of the split
+    pub min_second: u32,
+    /// Keys which move the divider towards the start of the first pane
+    pub decrease_keys: Vec<KeyboardInput>,
+    /// Keys which move the divider towards the end of the second pane
+    pub increase_keys: Vec<KeyboardInput>,
+}
+
+impl SplitStyle {
+    pub fn new(direction: SplitDirection) -> Self {
+        let divider = match direction {
+            SplitDirection::Horizontal => '│',
+            SplitDirection::Vertical => '─',
+        };
+        Self {
+            direction,
+            divider,
+            divider_style: Style::new().with_foreground(Rgb24::new_grey(255)),
+            min_first: 1,
+            min_second: 1,
+            decrease_keys: vec![KeyboardInput::new(Key::Char('<'))],
+            increase_keys: vec![KeyboardInput::new(Key::Char('>'))],
+        }
+    }
+
+    /// The length of the first pane when `length` cells (including the divider)
+    /// are split with the given ratio
+    fn first_length(&self, ratio: f64, length: u32) -> u32 {
+        let available = length.saturating_sub(1);
+        let first = (ratio * available as f64).round() as u32;
+        first
+            .min(available.saturating_sub(self.min_second))
+            .max(self.min_first.min(available))
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct SplitLimits {
+    last_rendered_offset: Coord,
+    last_rendered_size: Size,
+}
+
+impl SplitLimits {
+    pub fn new() -> Self {
+        Self {
+            last_rendered_offset: Coord::new(0, 0),
+            last_rendered_size: Size::new(0, 0),
+        }
+    }
+    fn relative_coord_from_screen_coord(self, coord: Coord) -> Option<Coord> {
+        let relative_coord = coord - self.last_rendered_offset;
+        if relative_coord.is_valid(self.last_rendered_size) {
+            Some(relative_coord)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SplitLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The position of the divider, stored as the fraction of the available space
+/// given to the first pane, so it is preserved when the split is resized.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct SplitState {
+    ratio: f64,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    dragging: bool,
+}
+
+impl SplitState {
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: ratio.clamp(0., 1.),
+            dragging: false,
+        }
+    }
+    pub fn ratio(self) -> f64 {
+        self.ratio
+    }
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = ratio.clamp(0., 1.);
+    }
+    pub fn is_dragging(self) -> bool {
+        self.dragging
+    }
+    fn set_first_length(&mut self, first_length: i32, style: &SplitStyle, limits: SplitLimits) {
+        let length = limits.last_rendered_size.get(style.direction.axis());
+        let available = length.saturating_sub(1);
+        if available > 0 {
+            let first_length = (first_length.max(0) as u32).min(available);
+            let ratio = first_length as f64 / available as f64;
+            self.ratio = style.first_length(ratio, length) as f64 / available as f64;
+        }
+    }
+    /// Move the divider by `num_cells` cells
+    pub fn move_divider(&mut self, num_cells: i32, style: &SplitStyle, limits: SplitLimits) {
+        let length = limits.last_rendered_size.get(style.direction.axis());
+        let first_length = style.first_length(self.ratio, length) as i32;
+        self.set_first_length(first_length + num_cells, style, limits);
+    }
+    /// Update the position of the divider in response to input, using the geometry
+    /// recorded in `limits` the last time the view was rendered. The divider can be
+    /// dragged with the left mouse button, and moved by a cell at a time with the
+    /// keys in `style`.
+    pub fn on_input(&mut self, input: Input, style: &SplitStyle, limits: SplitLimits) {
+        let axis = style.direction.axis();
+        match input {
+            Input::Keyboard(keyboard_input) => {
+                if style.decrease_keys.contains(&keyboard_input) {
+                    self.move_divider(-1, style, limits);
+                } else if style.increase_keys.contains(&keyboard_input) {
+                    self.move_divider(1, style, limits);
+                }
+            }
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => {
+                if let Some(relative_coord) = limits.relative_coord_from_screen_coord(coord) {
+                    let length = limits.last_rendered_size.get(axis);
+                    let first_length = style.first_length(self.ratio, length) as i32;
+                    if relative_coord.get(axis) == first_length {
+                        self.dragging = true;
+                    }
+                }
+            }
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord,
+            }) if self.dragging => {
+                let relative_coord = coord - limits.last_rendered_offset;
+                self.set_first_length(relative_coord.get(axis), style, limits);
+            }
+            Input::Mouse(MouseInput::MouseMove { .. })
+            | Input::Mouse(MouseInput::MouseRelease { .. }) => self.dragging = false,
+            _ => (),
+        }
+    }
+}
+
+impl Default for SplitState {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+/// Lays out two views next to each other, separated by a divider. Both views
+/// are passed the same data.
+pub struct SplitView<'s, 'l, A, B> {
+    pub first: A,
+    pub second: B,
+    pub style: &'s SplitStyle,
+    pub limits: &'l mut SplitLimits,
+    pub state: SplitState,
+}
+
+impl<'s, 'l, A, B, T> View<T> for SplitView<'s, 'l, A, B>
+where
+    A: View<T>,
+    B: View<T>,
+    T: Clone,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        self.limits.last_rendered_offset = context.offset;
+        self.limits.last_rendered_size = context.size;
+        let axis = self.style.direction.axis();
+        let length = context.size.get(axis);
+        let first_length = self.style.first_length(self.state.ratio, length);
+        let first_size = context.size.set(axis, first_length);
+        self.first
+            .view(data.clone(), context.constrain_size_to(first_size), frame);
+        let divider_cell = ViewCell::new()
+            .with_character(self.style.divider)
+            .with_style(self.style.divider_style);
+        for i in 0..(context.size.get(axis.other()) as i32) {
+            frame.set_cell_relative(
+                axis.new_coord(first_length as i32, i),
+                0,
+                divider_cell,
+                context,
+            );
+        }
+        self.second.view(
+            data,
+            context.add_offset(axis.new_coord(first_length as i32 + 1, 0)),
+            frame,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    struct Fill(char);
+
+    impl View<()> for Fill {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            let view_cell = ViewCell::new().with_character(self.0);
+            for coord in context.size.coord_iter_row_major() {
+                frame.set_cell_relative(coord, 0, view_cell, context);
+            }
+        }
+    }
+
+    fn render(style: &SplitStyle, limits: &mut SplitLimits, state: SplitState) -> Vec<String> {
+        let size = Size::new(7, 1);
+        let mut test_grid = TestGrid::new(size);
+        SplitView {
+            first: Fill('a'),
+            second: Fill('b'),
+            style,
+            limits,
+            state,
+        }
+        .view((), ViewContext::default_with_size(size), &mut test_grid);
+        test_grid.string_rows()
+    }
+
+    #[test]
+    fn move_divider_with_keys_and_mouse() {
+        let style = SplitStyle::new(SplitDirection::Horizontal);
+        let mut limits = SplitLimits::new();
+        let mut state = SplitState::new(0.5);
+        assert_eq!(render(&style, &mut limits, state), &["aaa│bbb"]);
+        state.on_input(
+            Input::Keyboard(KeyboardInput::new(Key::Char('>'))),
+            &style,
+            limits,
+        );
+        assert_eq!(render(&style, &mut limits, state), &["aaaa│bb"]);
+        state.on_input(
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord: Coord::new(4, 0),
+            }),
+            &style,
+            limits,
+        );
+        assert!(state.is_dragging());
+        state.on_input(
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord: Coord::new(0, 0),
+            }),
+            &style,
+            limits,
+        );
+        assert_eq!(render(&style, &mut limits, state), &["a│bbbbb"]);
+    }
+}
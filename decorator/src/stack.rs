@@ -0,0 +1,196 @@
+use crate::{AlignView, Alignment, AlignmentX, AlignmentY};
+use chargrid_render::*;
+
+/// A view in a `StackView`, with its alignment within the stack
+pub struct StackLayer<V> {
+    pub view: V,
+    pub alignment: Alignment,
+}
+
+impl<V> StackLayer<V> {
+    /// A layer aligned to the top-left of the stack
+    pub fn new(view: V) -> Self {
+        Self {
+            view,
+            alignment: Alignment::new(AlignmentX::Left, AlignmentY::Top),
+        }
+    }
+    pub fn with_alignment(self, alignment: Alignment) -> Self {
+        Self { alignment, ..self }
+    }
+}
+
+/// Collections of layers which can be drawn by a `StackView`. This is implemented
+/// for tuples of up to 8 `StackLayer`s of different types, and for `Vec`s of
+/// `StackLayer`s of the same type.
+pub trait StackLayers<T> {
+    fn view_layers<F: Frame, C: ColModify>(
+        &mut self,
+        data: T,
+        depth_step: i8,
+        context: ViewContext<C>,
+        frame: &mut F,
+    );
+    fn layers_size<C: ColModify>(&mut self, data: T, context: ViewContext<C>) -> Size;
+}
+
+fn layer_depth(index: usize, depth_step: i8) -> i8 {
+    (index as i64 * depth_step as i64).clamp(i8::MIN as i64, i8::MAX as i64) as i8
+}
+
+macro_rules! impl_stack_layers {
+    ($($index:tt: $type:ident),*) => {
+        impl<T, $($type),*> StackLayers<T> for ($(StackLayer<$type>,)*)
+        where
+            T: Clone,
+            $($type: View<T>,)*
+        {
+            fn view_layers<F: Frame, C: ColModify>(
+                &mut self,
+                data: T,
+                depth_step: i8,
+                context: ViewContext<C>,
+                frame: &mut F,
+            ) {
+                $(
+                    AlignView {
+                        view: &mut self.$index.view,
+                        alignment: self.$index.alignment,
+                    }
+                    .view(
+                        data.clone(),
+                        context.add_depth(layer_depth($index, depth_step)),
+                        frame,
+                    );
+                )*
+            }
+            fn layers_size<C: ColModify>(&mut self, data: T, context: ViewContext<C>) -> Size {
+                let size = Size::new(0, 0);
+                $(
+                    let size = size.pairwise_max(self.$index.view.size(data.clone(), context));
+                )*
+                size
+            }
+        }
+    };
+}
+
+impl_stack_layers!(0: V0);
+impl_stack_layers!(0: V0, 1: V1);
+impl_stack_layers!(0: V0, 1: V1, 2: V2);
+impl_stack_layers!(0: V0, 1: V1, 2: V2, 3: V3);
+impl_stack_layers!(0: V0, 1: V1, 2: V2, 3: V3, 4: V4);
+impl_stack_layers!(0: V0, 1: V1, 2: V2, 3: V3, 4: V4, 5: V5);
+impl_stack_layers!(0: V0, 1: V1, 2: V2, 3: V3, 4: V4, 5: V5, 6: V6);
+impl_stack_layers!(0: V0, 1: V1, 2: V2, 3: V3, 4: V4, 5: V5, 6: V6, 7: V7);
+
+impl<V, T> StackLayers<T> for Vec<StackLayer<V>>
+where
+    V: View<T>,
+    T: Clone,
+{
+    fn view_layers<F: Frame, C: ColModify>(
+        &mut self,
+        data: T,
+        depth_step: i8,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) {
+        for (index, layer) in self.iter_mut().enumerate() {
+            AlignView {
+                view: &mut layer.view,
+                alignment: layer.alignment,
+            }
+            .view(
+                data.clone(),
+                context.add_depth(layer_depth(index, depth_step)),
+                frame,
+            );
+        }
+    }
+    fn layers_size<C: ColModify>(&mut self, data: T, context: ViewContext<C>) -> Size {
+        self.iter_mut().fold(Size::new(0, 0), |size, layer| {
+            size.pairwise_max(layer.view.size(data.clone(), context))
+        })
+    }
+}
+
+/// Draws several views over the same area. Each layer is aligned within the
+/// stack, and drawn `depth_step` layers above the previous one, so later layers
+/// appear on top of earlier ones. All layers are passed the same data.
+pub struct StackView<L> {
+    pub layers: L,
+    pub depth_step: i8,
+}
+
+impl<L> StackView<L> {
+    pub fn new(layers: L) -> Self {
+        Self {
+            layers,
+            depth_step: 1,
+        }
+    }
+}
+
+impl<L, T> View<T> for StackView<L>
+where
+    L: StackLayers<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        self.layers
+            .view_layers(data, self.depth_step, context, frame);
+    }
+
+    fn size<C: ColModify>(&mut self, data: T, context: ViewContext<C>) -> Size {
+        self.layers.layers_size(data, context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    struct Block(Size, char);
+
+    impl View<()> for Block {
+        fn view<F: Frame, C: ColModify>(&mut self, _: (), context: ViewContext<C>, frame: &mut F) {
+            for y in 0..self.0.height() as i32 {
+                for x in 0..self.0.width() as i32 {
+                    let view_cell = ViewCell::new().with_character(self.1);
+                    frame.set_cell_relative(Coord::new(x, y), 0, view_cell, context);
+                }
+            }
+        }
+    }
+
+    fn stack(depth_step: i8) -> StackView<(StackLayer<Block>, StackLayer<Block>)> {
+        StackView {
+            layers: (
+                StackLayer::new(Block(Size::new(2, 1), '#')).with_alignment(Alignment::centre()),
+                StackLayer::new(Block(Size::new(4, 3), '.')),
+            ),
+            depth_step,
+        }
+    }
+
+    #[test]
+    fn later_layers_are_drawn_above_earlier_ones() {
+        let size = Size::new(6, 4);
+        // start above the grid's initial depth so a negative step is still drawn
+        let context = ViewContext::default_with_size(size).add_depth(2);
+        assert_eq!(stack(1).size((), context), Size::new(4, 3));
+        let mut test_grid = TestGrid::new(size);
+        stack(1).view((), context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["....  ", "....  ", "....  ", "      "]
+        );
+        let mut test_grid = TestGrid::new(size);
+        stack(-1).view((), context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["....  ", "..##  ", "....  ", "      "]
+        );
+    }
+}
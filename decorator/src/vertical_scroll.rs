@@ -1,3 +1,4 @@
+use chargrid_input::{Input, MouseButton, MouseInput, ScrollDirection};
 use chargrid_render::*;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
@@ -15,12 +16,16 @@ pub struct VerticalScrollBarStyle {
 pub struct VerticalScrollLimits {
     last_rendered_inner_height: u32,
     last_rendered_outer_height: u32,
+    last_rendered_offset: Coord,
+    last_rendered_width: u32,
 }
 
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy)]
 pub struct VerticalScrollState {
     scroll_position: u32,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    drag_grab_offset: Option<u32>,
 }
 
 pub struct VerticalScrollView<'s, 'l, V> {
@@ -51,12 +56,50 @@ impl VerticalScrollLimits {
         Self {
             last_rendered_inner_height: 0,
             last_rendered_outer_height: 0,
+            last_rendered_offset: Coord::new(0, 0),
+            last_rendered_width: 0,
         }
     }
     pub fn max_scroll_position(self) -> u32 {
         self.last_rendered_inner_height
             .saturating_sub(self.last_rendered_outer_height)
     }
+    fn scroll_bar_geometry(self, scroll_position: u32) -> Option<ScrollBarGeometry> {
+        if self.last_rendered_inner_height > self.last_rendered_outer_height {
+            let height = ((self.last_rendered_outer_height * self.last_rendered_outer_height)
+                / self.last_rendered_inner_height)
+                .max(1);
+            let track = self.last_rendered_outer_height - height;
+            let top = (track * scroll_position) / self.max_scroll_position();
+            Some(ScrollBarGeometry { top, height, track })
+        } else {
+            None
+        }
+    }
+    fn relative_coord_from_screen_coord(self, coord: Coord) -> Option<Coord> {
+        let relative_coord = coord - self.last_rendered_offset;
+        if relative_coord.x < 0
+            || relative_coord.y < 0
+            || relative_coord.x >= self.last_rendered_width as i32
+            || relative_coord.y >= self.last_rendered_outer_height as i32
+        {
+            None
+        } else {
+            Some(relative_coord)
+        }
+    }
+    fn scroll_bar_y_from_screen_coord(self, coord: Coord) -> Option<u32> {
+        self.relative_coord_from_screen_coord(coord)
+            .filter(|relative_coord| relative_coord.x == self.last_rendered_width as i32 - 1)
+            .map(|relative_coord| relative_coord.y as u32)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScrollBarGeometry {
+    top: u32,
+    height: u32,
+    track: u32,
 }
 
 impl Default for VerticalScrollLimits {
@@ -67,7 +110,10 @@ impl Default for VerticalScrollLimits {
 
 impl VerticalScrollState {
     pub fn new() -> Self {
-        Self { scroll_position: 0 }
+        Self {
+            scroll_position: 0,
+            drag_grab_offset: None,
+        }
     }
     pub fn scroll_to(&mut self, scroll_position: u32, limits: VerticalScrollLimits) {
         self.scroll_position = scroll_position.min(limits.max_scroll_position());
@@ -106,9 +152,82 @@ impl VerticalScrollState {
     pub fn scroll_to_bottom(&mut self, limits: VerticalScrollLimits) {
         self.scroll_position = limits.max_scroll_position();
     }
+    /// Scroll as little as possible so that `line` is visible, without scrolling
+    /// past the end of `num_lines` lines of content. Unlike the other methods this
+    /// doesn't rely on the height of the content when it was last rendered, so it
+    /// can be used right after the content changes.
+    pub fn reveal_line(&mut self, line: u32, num_lines: u32, limits: VerticalScrollLimits) {
+        let height = limits.last_rendered_outer_height;
+        if line < self.scroll_position {
+            self.scroll_position = line;
+        } else if height > 0 && line >= self.scroll_position + height {
+            self.scroll_position = line + 1 - height;
+        }
+        self.scroll_position = self.scroll_position.min(num_lines.saturating_sub(height));
+    }
     pub fn scroll_position(self) -> u32 {
         self.scroll_position
     }
+    pub fn is_dragging(self) -> bool {
+        self.drag_grab_offset.is_some()
+    }
+    /// Update the scroll position in response to mouse input, using the geometry
+    /// recorded in `limits` the last time the view was rendered. Scrolling the wheel
+    /// over the view scrolls by a line, clicking the track above or below the bar
+    /// scrolls by a page, and pressing on the bar itself starts dragging it.
+    pub fn on_input(&mut self, input: Input, limits: VerticalScrollLimits) {
+        match input {
+            Input::Mouse(MouseInput::MouseScroll { direction, coord })
+                if limits.relative_coord_from_screen_coord(coord).is_some() =>
+            {
+                match direction {
+                    ScrollDirection::Up => self.scroll_up_line(limits),
+                    ScrollDirection::Down => self.scroll_down_line(limits),
+                    ScrollDirection::Left | ScrollDirection::Right => (),
+                }
+            }
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => {
+                if let (Some(bar_y), Some(geometry)) = (
+                    limits.scroll_bar_y_from_screen_coord(coord),
+                    limits.scroll_bar_geometry(self.scroll_position),
+                ) {
+                    if bar_y < geometry.top {
+                        self.scroll_up_page(limits);
+                    } else if bar_y >= geometry.top + geometry.height {
+                        self.scroll_down_page(limits);
+                    } else {
+                        self.drag_grab_offset = Some(bar_y - geometry.top);
+                    }
+                }
+            }
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord,
+            }) => {
+                if let (Some(drag_grab_offset), Some(geometry)) = (
+                    self.drag_grab_offset,
+                    limits.scroll_bar_geometry(self.scroll_position),
+                ) {
+                    if geometry.track > 0 {
+                        let bar_top =
+                            (coord.y - limits.last_rendered_offset.y - drag_grab_offset as i32)
+                                .max(0) as u32;
+                        let bar_top = bar_top.min(geometry.track);
+                        let scroll_position = (bar_top * limits.max_scroll_position()
+                            + geometry.track / 2)
+                            / geometry.track;
+                        self.scroll_to(scroll_position, limits);
+                    }
+                }
+            }
+            Input::Mouse(MouseInput::MouseMove { .. })
+            | Input::Mouse(MouseInput::MouseRelease { .. }) => self.drag_grab_offset = None,
+            _ => (),
+        }
+    }
 }
 
 impl Default for VerticalScrollState {
@@ -124,19 +243,14 @@ fn render_scroll_bar<F: Frame, C: ColModify>(
     context: ViewContext<C>,
     frame: &mut F,
 ) {
-    if limits.last_rendered_inner_height > limits.last_rendered_outer_height {
+    if let Some(geometry) = limits.scroll_bar_geometry(state.scroll_position) {
         let view_cell = ViewCell {
             style: scroll_bar_style.style,
             character: Some(scroll_bar_style.character),
         };
         let bar_x = context.size.width() as i32 - 1;
-        let bar_height = (limits.last_rendered_outer_height * limits.last_rendered_outer_height)
-            / limits.last_rendered_inner_height;
-        let bar_top = ((limits.last_rendered_outer_height - bar_height)
-            * state.scroll_position as u32)
-            / limits.max_scroll_position() as u32;
-        for y in 0..bar_height {
-            let bar_y = (y + bar_top) as i32;
+        for y in 0..geometry.height {
+            let bar_y = (y + geometry.top) as i32;
             let coord = Coord::new(bar_x, bar_y);
             frame.set_cell_relative(coord, 0, view_cell, context);
         }
@@ -233,6 +347,79 @@ where
             blend,
         );
     }
+
+    fn blend_cell_foreground_relative<C: ColModify, B: Blend>(
+        &mut self,
+        relative_coord: Coord,
+        relative_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+        context: ViewContext<C>,
+    ) {
+        let adjusted_relative_coord = relative_coord - self.offset;
+        self.max_y = self.max_y.max((relative_coord + context.offset).y);
+        if adjusted_relative_coord.is_valid(context.size) {
+            let absolute_coord = adjusted_relative_coord + context.offset;
+            let absolute_depth = relative_depth + context.depth;
+            if let Some(modified_rgb24) = context.col_modify.foreground(Some(rgb24)) {
+                self.blend_cell_foreground_absolute(
+                    absolute_coord,
+                    absolute_depth,
+                    modified_rgb24,
+                    alpha,
+                    blend,
+                );
+            }
+        }
+    }
+
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        absolute_coord: Coord,
+        absolute_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+    ) {
+        self.frame.blend_cell_foreground_absolute(
+            absolute_coord,
+            absolute_depth,
+            rgb24,
+            alpha,
+            blend,
+        );
+    }
+}
+
+fn vertical_scroll_view<V, T, F, C>(
+    mut view: V,
+    data: T,
+    scroll_bar_style: &VerticalScrollBarStyle,
+    limits: &mut VerticalScrollLimits,
+    state: VerticalScrollState,
+    context: ViewContext<C>,
+    frame: &mut F,
+) where
+    V: View<T>,
+    C: ColModify,
+    F: Frame,
+{
+    let mut partial_frame = PartialFrame {
+        offset: Coord::new(0, state.scroll_position as i32),
+        max_y: 0,
+        frame,
+    };
+    view.view(
+        data,
+        context.constrain_size_by(Size::new(1 + scroll_bar_style.left_padding, 0)),
+        &mut partial_frame,
+    );
+    limits.last_rendered_inner_height = (partial_frame.max_y - context.offset.y).max(0) as u32 + 1;
+    limits.last_rendered_outer_height = context.size.height();
+    limits.last_rendered_offset = context.offset;
+    limits.last_rendered_width = context.size.width();
+    render_scroll_bar(scroll_bar_style, state, *limits, context, frame);
 }
 
 impl<'s, 'l, V, T> View<T> for VerticalScrollView<'s, 'l, V>
@@ -240,25 +427,115 @@ where
     V: View<T>,
 {
     fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
-        let mut partial_frame = PartialFrame {
-            offset: Coord::new(0, self.state.scroll_position as i32),
-            max_y: 0,
-            frame,
-        };
-        self.view.view(
+        vertical_scroll_view(
+            &mut self.view,
             data,
-            context.constrain_size_by(Size::new(1 + self.scroll_bar_style.left_padding, 0)),
-            &mut partial_frame,
-        );
-        self.limits.last_rendered_inner_height =
-            (partial_frame.max_y - context.offset.y).max(0) as u32 + 1;
-        self.limits.last_rendered_outer_height = context.size.height();
-        render_scroll_bar(
             self.scroll_bar_style,
+            self.limits,
+            self.state,
+            context,
+            frame,
+        );
+    }
+}
+
+/// A `VerticalScrollView` which owns its style, limits and state
+pub struct VerticalScrollViewOwned<V> {
+    pub view: V,
+    pub scroll_bar_style: VerticalScrollBarStyle,
+    pub limits: VerticalScrollLimits,
+    pub state: VerticalScrollState,
+}
+
+impl<V> VerticalScrollViewOwned<V> {
+    pub fn new(view: V, scroll_bar_style: VerticalScrollBarStyle) -> Self {
+        Self {
+            view,
+            scroll_bar_style,
+            limits: VerticalScrollLimits::new(),
+            state: VerticalScrollState::new(),
+        }
+    }
+    pub fn on_input(&mut self, input: Input) {
+        self.state.on_input(input, self.limits);
+    }
+}
+
+impl<V, T> View<T> for VerticalScrollViewOwned<V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        vertical_scroll_view(
+            &mut self.view,
+            data,
+            &self.scroll_bar_style,
+            &mut self.limits,
             self.state,
-            *self.limits,
             context,
             frame,
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    struct Digits;
+
+    impl View<u32> for Digits {
+        fn view<F: Frame, C: ColModify>(
+            &mut self,
+            num_lines: u32,
+            context: ViewContext<C>,
+            frame: &mut F,
+        ) {
+            for y in 0..num_lines {
+                let character = std::char::from_digit(y % 10, 10).unwrap();
+                let view_cell = ViewCell::new().with_character(character);
+                frame.set_cell_relative(Coord::new(0, y as i32), 0, view_cell, context);
+            }
+        }
+    }
+
+    fn press(coord: Coord) -> Input {
+        Input::Mouse(MouseInput::MousePress {
+            button: MouseButton::Left,
+            coord,
+        })
+    }
+
+    fn drag(coord: Coord) -> Input {
+        Input::Mouse(MouseInput::MouseMove {
+            button: Some(MouseButton::Left),
+            coord,
+        })
+    }
+
+    #[test]
+    fn click_track_and_drag_bar() {
+        let size = Size::new(3, 4);
+        let style = VerticalScrollBarStyle::new();
+        let mut view = VerticalScrollViewOwned::new(Digits, style);
+        let mut test_grid = TestGrid::new(size);
+        let context = ViewContext::default_with_size(size);
+        view.view(8, context, &mut test_grid);
+        assert_eq!(test_grid.string_rows(), &["0 █", "1 █", "2  ", "3  "]);
+        view.on_input(press(Coord::new(2, 3)));
+        assert_eq!(view.state.scroll_position(), 4);
+        view.on_input(press(Coord::new(2, 2)));
+        assert!(view.state.is_dragging());
+        view.on_input(drag(Coord::new(2, 1)));
+        assert_eq!(view.state.scroll_position(), 2);
+        let mut test_grid = TestGrid::new(size);
+        view.view(8, context, &mut test_grid);
+        assert_eq!(test_grid.string_rows(), &["2  ", "3 █", "4 █", "5  "]);
+        view.on_input(Input::Mouse(MouseInput::MouseRelease {
+            button: Ok(MouseButton::Left),
+            coord: Coord::new(2, 1),
+        }));
+        assert!(!view.state.is_dragging());
+    }
+}
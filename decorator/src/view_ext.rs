@@ -0,0 +1,103 @@
+use crate::*;
+use chargrid_render::*;
+
+/// Chainable methods for wrapping views in decorators, as an alternative to
+/// nesting decorator struct literals. Decorators which would otherwise borrow
+/// their style take ownership of it, so chains can be stored in structs.
+/// For example, `view.pad(Size::new(2, 0)).border(BorderStyle::new())`.
+pub trait ViewExt: Sized {
+    fn bound(self, size: Size) -> BoundView<Self> {
+        BoundView { view: self, size }
+    }
+    fn min_size(self, size: Size) -> MinSizeView<Self> {
+        MinSizeView { view: self, size }
+    }
+    fn max_size(self, size: Size) -> MaxSizeView<Self> {
+        MaxSizeView { view: self, size }
+    }
+    fn align(self, alignment: Alignment) -> AlignView<Self> {
+        AlignView {
+            view: self,
+            alignment,
+        }
+    }
+    fn pad(self, size: Size) -> PadView<Self> {
+        PadView { view: self, size }
+    }
+    fn border(self, style: BorderStyle) -> BorderViewOwned<Self> {
+        BorderViewOwned { view: self, style }
+    }
+    fn fill_background(self, rgb24: Rgb24) -> FillBackgroundView<Self> {
+        FillBackgroundView { view: self, rgb24 }
+    }
+    fn col_modify<C: ColModify>(self, col_modify: C) -> ColModifyView<Self, C> {
+        ColModifyView {
+            view: self,
+            col_modify,
+        }
+    }
+    fn scroll(self, scroll_bar_style: VerticalScrollBarStyle) -> VerticalScrollViewOwned<Self> {
+        VerticalScrollViewOwned::new(self, scroll_bar_style)
+    }
+}
+
+impl<V> ViewExt for V {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    struct Digits;
+
+    impl View<u32> for Digits {
+        fn view<F: Frame, C: ColModify>(
+            &mut self,
+            num_lines: u32,
+            context: ViewContext<C>,
+            frame: &mut F,
+        ) {
+            for y in 0..num_lines {
+                let character = std::char::from_digit(y % 10, 10).unwrap();
+                let view_cell = ViewCell::new().with_character(character);
+                frame.set_cell_relative(Coord::new(0, y as i32), 0, view_cell, context);
+            }
+        }
+    }
+
+    struct Ui {
+        digits: VerticalScrollViewOwned<BorderViewOwned<AlignView<BoundView<Digits>>>>,
+    }
+
+    impl Ui {
+        fn new() -> Self {
+            Self {
+                digits: Digits
+                    .bound(Size::new(1, 2))
+                    .align(Alignment::centre())
+                    .border(BorderStyle::new())
+                    .scroll(VerticalScrollBarStyle::new()),
+            }
+        }
+    }
+
+    #[test]
+    fn chain_stored_in_struct() {
+        let size = Size::new(8, 6);
+        let context = ViewContext::default_with_size(size);
+        let mut ui = Ui::new();
+        let mut test_grid = TestGrid::new(size);
+        ui.digits.view(2, context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &[
+                "┌──┐    ",
+                "│ 0│    ",
+                "│ 1│    ",
+                "└──┘    ",
+                "        ",
+                "        "
+            ]
+        );
+    }
+}
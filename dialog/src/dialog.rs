@@ -0,0 +1,531 @@
+use chargrid_decorator::{BorderPadding, BorderStyle, BorderView, FillBackgroundView};
+use chargrid_edit::{LineEditorRoutine, LineEditorState, LineEditorStyle, LineEditorView};
+use chargrid_event_routine::{
+    event_or_peek_with_handled, EventOrPeek, EventRoutine, Handled, ViewSelector,
+};
+use chargrid_input::Input;
+use chargrid_menu::{
+    Escape, MenuEntryString, MenuEntryToRender, MenuInstance, MenuInstanceBuilder,
+    StaticStyleMenuInstanceModel, StaticStyleMenuInstanceView,
+};
+use chargrid_render::*;
+use chargrid_text::{wrap, TextView};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct DialogStyle {
+    pub border: BorderStyle,
+    pub background: Rgb24,
+    pub message: Style,
+    pub normal: Style,
+    pub selected: Style,
+    /// The width at which the message is wrapped
+    pub max_width: u32,
+    /// The text entry of prompt dialogs
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub line_editor: LineEditorStyle,
+}
+
+impl Default for DialogStyle {
+    fn default() -> Self {
+        Self {
+            border: BorderStyle {
+                padding: BorderPadding::all(1),
+                ..BorderStyle::new()
+            },
+            background: Rgb24::new_grey(31),
+            message: Style::new().with_foreground(Rgb24::new_grey(255)),
+            normal: Style::new().with_foreground(Rgb24::new_grey(127)),
+            selected: Style::new()
+                .with_bold(true)
+                .with_foreground(Rgb24::new_grey(255)),
+            max_width: 40,
+            line_editor: LineEditorStyle::default(),
+        }
+    }
+}
+
+/// View state for dialogs. An application using dialogs stores one of these
+/// in its view, and passes a `ViewSelector` which finds it to the dialog routines.
+pub struct DialogView {
+    menu: StaticStyleMenuInstanceView,
+    line_editor: LineEditorView,
+    style: DialogStyle,
+}
+
+impl DialogView {
+    pub fn new(style: DialogStyle) -> Self {
+        Self {
+            menu: StaticStyleMenuInstanceView::new(style.normal, style.selected),
+            line_editor: LineEditorView::new(style.line_editor.clone()),
+            style,
+        }
+    }
+    pub fn style(&self) -> &DialogStyle {
+        &self.style
+    }
+}
+
+impl Default for DialogView {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+struct Labels<'a>(&'a [String]);
+
+impl<'a> MenuEntryString for Labels<'a> {
+    type Entry = usize;
+    fn render_string(&self, entry: MenuEntryToRender<Self::Entry>, buf: &mut String) {
+        buf.push_str(&self.0[*entry.entry]);
+    }
+}
+
+struct DialogContentView<'a> {
+    message: &'a str,
+    message_style: Style,
+    max_width: u32,
+    menu: &'a mut StaticStyleMenuInstanceView,
+    menu_instance: &'a MenuInstance<usize>,
+    labels: &'a [String],
+}
+
+impl<'a> View<()> for DialogContentView<'a> {
+    fn view<F: Frame, C: ColModify>(&mut self, (): (), context: ViewContext<C>, frame: &mut F) {
+        let context = context.constrain_size_to(Size::new(self.max_width, context.size.height()));
+        let menu_offset_y = if self.message.is_empty() {
+            0
+        } else {
+            let message_size = TextView::new(self.message_style, wrap::Word::new()).view_size(
+                &[self.message],
+                context,
+                frame,
+            );
+            message_size.height() as i32 + 1
+        };
+        self.menu.view(
+            StaticStyleMenuInstanceModel::new(self.menu_instance, &Labels(self.labels)),
+            context.add_offset(Coord::new(0, menu_offset_y)),
+            frame,
+        );
+    }
+}
+
+/// An event routine which displays a message above a list of choices. It returns
+/// the value associated with the chosen option, or `None` if escape is pressed.
+/// Combine with `Modal` to draw it over the rest of the application.
+pub struct DialogRoutine<S, D, T> {
+    s: S,
+    data: PhantomData<D>,
+    message: String,
+    labels: Vec<String>,
+    values: Vec<T>,
+    menu_instance: MenuInstance<usize>,
+}
+
+impl<S, D, T> DialogRoutine<S, D, T>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+{
+    /// Each choice is a label and the value to return if it is chosen. Choices can be
+    /// made with the first character of their label as a hotkey, unless more than one
+    /// label starts with the same character.
+    /// Panics if there are no choices.
+    pub fn new<M: Into<String>, L: Into<String>>(s: S, message: M, choices: Vec<(L, T)>) -> Self {
+        let (labels, values): (Vec<String>, Vec<T>) = choices
+            .into_iter()
+            .map(|(label, value)| (label.into(), value))
+            .unzip();
+        let mut hotkeys = HashMap::new();
+        let mut ambiguous = Vec::new();
+        for (i, label) in labels.iter().enumerate() {
+            if let Some(ch) = label.chars().next() {
+                let mut cases = ch
+                    .to_lowercase()
+                    .chain(ch.to_uppercase())
+                    .collect::<Vec<_>>();
+                cases.dedup();
+                for ch in cases {
+                    if hotkeys.insert(ch, i).is_some() {
+                        ambiguous.push(ch);
+                    }
+                }
+            }
+        }
+        for ch in ambiguous {
+            hotkeys.remove(&ch);
+        }
+        let menu_instance = MenuInstanceBuilder {
+            items: (0..labels.len()).collect(),
+            selected_index: 0,
+            hotkeys: Some(hotkeys),
+        }
+        .build()
+        .expect("dialog must have at least one choice");
+        Self {
+            s,
+            data: PhantomData,
+            message: message.into(),
+            labels,
+            values,
+            menu_instance,
+        }
+    }
+}
+
+impl<S, D, T> EventRoutine for DialogRoutine<S, D, T>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+    T: Clone,
+{
+    type Return = Option<T>;
+    type Data = D;
+    type View = S::ViewInput;
+    type Event = Input;
+
+    fn handle<EP>(
+        self,
+        _data: &mut Self::Data,
+        view: &Self::View,
+        event_or_peek: EP,
+    ) -> Handled<Self::Return, Self>
+    where
+        EP: EventOrPeek<Event = Self::Event>,
+    {
+        event_or_peek_with_handled(event_or_peek, self, |mut s, input| {
+            let dialog_view = s.s.view(view);
+            match s.menu_instance.choose_or_escape(&dialog_view.menu, input) {
+                Some(Ok(index)) => Handled::Return(Some(s.values[index].clone())),
+                Some(Err(Escape)) => Handled::Return(None),
+                None => Handled::Continue(s),
+            }
+        })
+    }
+
+    fn view<F, C>(
+        &self,
+        _data: &Self::Data,
+        view: &mut Self::View,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        F: Frame,
+        C: ColModify,
+    {
+        let DialogView { menu, style, .. } = self.s.view_mut(view);
+        FillBackgroundView {
+            rgb24: style.background,
+            view: BorderView {
+                style: &style.border,
+                view: DialogContentView {
+                    message: &self.message,
+                    message_style: style.message,
+                    max_width: style.max_width,
+                    menu,
+                    menu_instance: &self.menu_instance,
+                    labels: &self.labels,
+                },
+            },
+        }
+        .view((), context, frame);
+    }
+}
+
+/// A dialog with a list of choices. Returns `None` if escape is pressed.
+/// Panics if there are no choices.
+pub fn choose<S, D, T, M, L>(
+    s: S,
+    message: M,
+    choices: Vec<(L, T)>,
+) -> impl EventRoutine<Return = Option<T>, Data = D, View = S::ViewInput, Event = Input>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+    T: Clone,
+    M: Into<String>,
+    L: Into<String>,
+{
+    DialogRoutine::new(s, message, choices)
+}
+
+/// Finds the line editor of the `DialogView` found by `S`
+struct LineEditorSelector<S>(S);
+
+impl<S> ViewSelector for LineEditorSelector<S>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+{
+    type ViewInput = S::ViewInput;
+    type ViewOutput = LineEditorView;
+    fn view<'a>(&self, input: &'a Self::ViewInput) -> &'a Self::ViewOutput {
+        &self.0.view(input).line_editor
+    }
+    fn view_mut<'a>(&self, input: &'a mut Self::ViewInput) -> &'a mut Self::ViewOutput {
+        &mut self.0.view_mut(input).line_editor
+    }
+}
+
+/// Draws the message of a prompt, and leaves a line below it for the line
+/// editor, recording where that line ended up
+struct PromptContentView<'a> {
+    message: &'a str,
+    message_style: Style,
+    max_width: u32,
+    line_editor_offset: &'a mut Coord,
+}
+
+impl<'a> View<()> for PromptContentView<'a> {
+    fn view<F: Frame, C: ColModify>(&mut self, (): (), context: ViewContext<C>, frame: &mut F) {
+        let context = context.constrain_size_to(Size::new(self.max_width, context.size.height()));
+        let line_editor_y = if self.message.is_empty() {
+            0
+        } else {
+            let message_size = TextView::new(self.message_style, wrap::Word::new()).view_size(
+                &[self.message],
+                context,
+                frame,
+            );
+            message_size.height() as i32 + 1
+        };
+        *self.line_editor_offset = context.offset + Coord::new(0, line_editor_y);
+        // reserve the full width for the line editor
+        let width = self.max_width.min(context.size.width()) as i32;
+        if width > 0 {
+            frame.set_cell_relative(
+                Coord::new(width - 1, line_editor_y),
+                0,
+                ViewCell::new(),
+                context,
+            );
+        }
+    }
+}
+
+/// An event routine which displays a message above a line of text entry, built
+/// on a `LineEditorRoutine`. It returns the text when it's submitted, or `None` if
+/// escape is pressed. Combine with `Modal` to draw it over the rest of the
+/// application.
+pub struct PromptRoutine<S, D> {
+    s: S,
+    message: String,
+    line_editor: LineEditorRoutine<LineEditorSelector<S>, D>,
+}
+
+impl<S, D> PromptRoutine<S, D>
+where
+    S: ViewSelector<ViewOutput = DialogView> + Clone,
+{
+    /// `state` holds the initial text, and any limits on what can be entered
+    pub fn new<M: Into<String>>(s: S, message: M, state: LineEditorState) -> Self {
+        Self {
+            s: s.clone(),
+            message: message.into(),
+            line_editor: LineEditorRoutine::new(LineEditorSelector(s), state),
+        }
+    }
+}
+
+impl<S, D> EventRoutine for PromptRoutine<S, D>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+{
+    type Return = Option<String>;
+    type Data = D;
+    type View = S::ViewInput;
+    type Event = Input;
+
+    fn handle<EP>(
+        self,
+        data: &mut Self::Data,
+        view: &Self::View,
+        event_or_peek: EP,
+    ) -> Handled<Self::Return, Self>
+    where
+        EP: EventOrPeek<Event = Self::Event>,
+    {
+        let Self {
+            s,
+            message,
+            line_editor,
+        } = self;
+        line_editor
+            .handle(data, view, event_or_peek)
+            .map_continue(|line_editor| Self {
+                s,
+                message,
+                line_editor,
+            })
+    }
+
+    fn view<F, C>(
+        &self,
+        data: &Self::Data,
+        view: &mut Self::View,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        F: Frame,
+        C: ColModify,
+    {
+        let style = &self.s.view(view).style;
+        let mut line_editor_offset = context.offset;
+        FillBackgroundView {
+            rgb24: style.background,
+            view: BorderView {
+                style: &style.border,
+                view: PromptContentView {
+                    message: &self.message,
+                    message_style: style.message,
+                    max_width: style.max_width,
+                    line_editor_offset: &mut line_editor_offset,
+                },
+            },
+        }
+        .view((), context, frame);
+        let line_editor_context = context
+            .add_offset(line_editor_offset - context.offset)
+            .constrain_size_to(Size::new(style.max_width, 1));
+        self.line_editor
+            .view(data, view, line_editor_context, frame);
+    }
+}
+
+/// A dialog with a message above a line of text entry. Returns the entered text,
+/// or `None` if escape is pressed.
+pub fn prompt<S, D, M>(
+    s: S,
+    message: M,
+    state: LineEditorState,
+) -> impl EventRoutine<Return = Option<String>, Data = D, View = S::ViewInput, Event = Input>
+where
+    S: ViewSelector<ViewOutput = DialogView> + Clone,
+    M: Into<String>,
+{
+    PromptRoutine::new(s, message, state)
+}
+
+/// A dialog with "Yes" and "No" options. Returns `false` if escape is pressed.
+pub fn confirm<S, D, M>(
+    s: S,
+    message: M,
+) -> impl EventRoutine<Return = bool, Data = D, View = S::ViewInput, Event = Input>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+    M: Into<String>,
+{
+    DialogRoutine::new(s, message, vec![("Yes", true), ("No", false)])
+        .map(|maybe_confirmed| maybe_confirmed.unwrap_or(false))
+}
+
+/// A dialog with a single "OK" option, which returns when the message is dismissed
+pub fn message_box<S, D, M>(
+    s: S,
+    message: M,
+) -> impl EventRoutine<Return = (), Data = D, View = S::ViewInput, Event = Input>
+where
+    S: ViewSelector<ViewOutput = DialogView>,
+    M: Into<String>,
+{
+    DialogRoutine::new(s, message, vec![("OK", ())]).map(|_| ())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_event_routine::Event;
+    use chargrid_input::{keys, Key, KeyboardInput};
+    use chargrid_test_grid::TestGrid;
+
+    #[derive(Clone, Copy)]
+    struct SelectDialogView;
+
+    impl ViewSelector for SelectDialogView {
+        type ViewInput = DialogView;
+        type ViewOutput = DialogView;
+        fn view<'a>(&self, input: &'a Self::ViewInput) -> &'a Self::ViewOutput {
+            input
+        }
+        fn view_mut<'a>(&self, input: &'a mut Self::ViewInput) -> &'a mut Self::ViewOutput {
+            input
+        }
+    }
+
+    fn key(key: Key) -> Input {
+        Input::Keyboard(KeyboardInput::new(key))
+    }
+
+    /// Passes `inputs` to `routine` until it returns
+    fn run<R>(mut routine: R, view: &R::View, inputs: &[Input]) -> Option<R::Return>
+    where
+        R: EventRoutine<Data = (), Event = Input>,
+    {
+        for &input in inputs {
+            match routine.handle(&mut (), view, Event::new(input)) {
+                Handled::Return(r) => return Some(r),
+                Handled::Continue(r) => routine = r,
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn choose_from_list() {
+        let view = DialogView::default();
+        let choices = || vec![("Apple", 1), ("Banana", 2), ("Cherry", 3)];
+        let dialog = choose(SelectDialogView, "Pick one", choices());
+        let inputs = [key(Key::Down), Input::Keyboard(keys::RETURN)];
+        assert_eq!(run(dialog, &view, &inputs), Some(Some(2)));
+        let dialog = choose(SelectDialogView, "Pick one", choices());
+        assert_eq!(run(dialog, &view, &[key(Key::Char('c'))]), Some(Some(3)));
+        let dialog = choose(SelectDialogView, "Pick one", choices());
+        let inputs = [Input::Keyboard(keys::ESCAPE)];
+        assert_eq!(run(dialog, &view, &inputs), Some(None));
+    }
+
+    #[test]
+    fn prompt_for_text() {
+        let mut view = DialogView::new(DialogStyle {
+            max_width: 8,
+            ..Default::default()
+        });
+        let dialog = prompt(SelectDialogView, "Name?", LineEditorState::new());
+        let inputs = [
+            key(Key::Char('h')),
+            key(Key::Char('i')),
+            Input::Keyboard(keys::RETURN),
+        ];
+        assert_eq!(run(dialog, &view, &inputs), Some(Some("hi".to_string())));
+        let dialog = prompt(
+            SelectDialogView,
+            "Name?",
+            LineEditorState::new().with_text("abc"),
+        );
+        let size = Size::new(16, 9);
+        let mut test_grid = TestGrid::new(size);
+        dialog.view(
+            &(),
+            &mut view,
+            ViewContext::default_with_size(size),
+            &mut test_grid,
+        );
+        assert_eq!(
+            test_grid.string_rows(),
+            &[
+                "┌──────────┐    ",
+                "│          │    ",
+                "│ Name?    │    ",
+                "│          │    ",
+                "│ abc      │    ",
+                "│          │    ",
+                "└──────────┘    ",
+                "                ",
+                "                ",
+            ]
+        );
+        let inputs = [Input::Keyboard(keys::ESCAPE)];
+        assert_eq!(run(dialog, &view, &inputs), Some(None));
+    }
+}
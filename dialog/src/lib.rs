@@ -0,0 +1,5 @@
+mod dialog;
+pub use dialog::*;
+
+mod modal;
+pub use modal::*;
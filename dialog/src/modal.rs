@@ -0,0 +1,155 @@
+use chargrid_decorator::{AlignView, Alignment};
+use chargrid_event_routine::{Decorate, EventRoutine, EventRoutineView};
+use chargrid_render::*;
+
+/// Renders whatever is behind a modal dialog
+pub trait ModalBackground {
+    type Data;
+    type View;
+
+    fn view<F, C>(
+        &self,
+        data: &Self::Data,
+        view: &mut Self::View,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        F: Frame,
+        C: ColModify;
+}
+
+/// Decorates an event routine so that it appears as a modal dialog over a background.
+/// The background is dimmed, and the decorated routine is aligned within the context
+/// and drawn `depth` layers above the background. Since the decorated routine receives
+/// all events, the background is not interactive until it returns.
+pub struct Modal<B> {
+    pub background: B,
+    pub dim: u8,
+    pub alignment: Alignment,
+    pub depth: i8,
+}
+
+impl<B> Modal<B>
+where
+    B: ModalBackground,
+{
+    pub fn new(background: B) -> Self {
+        Self {
+            background,
+            dim: 127,
+            alignment: Alignment::centre(),
+            depth: 32,
+        }
+    }
+}
+
+impl<B> Decorate for Modal<B>
+where
+    B: ModalBackground,
+{
+    type View = B::View;
+    type Data = B::Data;
+
+    fn view<E, F, C>(
+        &self,
+        data: &Self::Data,
+        event_routine_view: EventRoutineView<E>,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        E: EventRoutine<Data = Self::Data, View = Self::View>,
+        F: Frame,
+        C: ColModify,
+    {
+        self.background.view(
+            data,
+            event_routine_view.view,
+            context.compose_col_modify(ColModifyDim(self.dim)),
+            frame,
+        );
+        AlignView {
+            alignment: self.alignment,
+            view: event_routine_view,
+        }
+        .view(data, context.add_depth(self.depth), frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_event_routine::{EventOrPeek, Handled};
+    use chargrid_input::Input;
+    use chargrid_test_grid::TestGrid;
+
+    const WHITE: Rgb24 = Rgb24::new_grey(255);
+
+    fn draw_text<F: Frame, C: ColModify>(text: &str, context: ViewContext<C>, frame: &mut F) {
+        for (x, ch) in text.chars().enumerate() {
+            let view_cell = ViewCell::new().with_character(ch).with_foreground(WHITE);
+            frame.set_cell_relative(Coord::new(x as i32, 0), 0, view_cell, context);
+        }
+    }
+
+    struct Dots;
+
+    impl ModalBackground for Dots {
+        type Data = ();
+        type View = ();
+        fn view<F, C>(&self, _data: &(), _view: &mut (), context: ViewContext<C>, frame: &mut F)
+        where
+            F: Frame,
+            C: ColModify,
+        {
+            for y in 0..context.size.height() as i32 {
+                draw_text("....", context.add_offset(Coord::new(0, y)), frame);
+            }
+        }
+    }
+
+    struct Message;
+
+    impl EventRoutine for Message {
+        type Return = ();
+        type Data = ();
+        type View = ();
+        type Event = Input;
+        fn handle<EP>(self, _data: &mut (), _view: &(), _event_or_peek: EP) -> Handled<(), Self>
+        where
+            EP: EventOrPeek<Event = Input>,
+        {
+            Handled::Continue(self)
+        }
+        fn view<F, C>(&self, _data: &(), _view: &mut (), context: ViewContext<C>, frame: &mut F)
+        where
+            F: Frame,
+            C: ColModify,
+        {
+            draw_text("ok", context, frame);
+        }
+    }
+
+    #[test]
+    fn dialog_drawn_over_dimmed_background() {
+        let size = Size::new(4, 3);
+        let mut test_grid = TestGrid::new(size);
+        Message.decorated(Modal::new(Dots)).view(
+            &(),
+            &mut (),
+            ViewContext::default_with_size(size),
+            &mut test_grid,
+        );
+        assert_eq!(test_grid.string_rows(), &["....", ".ok.", "...."]);
+        let foreground = |coord| {
+            test_grid
+                .view_cell(coord)
+                .and_then(|view_cell| view_cell.style.foreground)
+        };
+        assert_eq!(
+            foreground(Coord::new(0, 1)),
+            Some(WHITE.normalised_scalar_mul(127))
+        );
+        assert_eq!(foreground(Coord::new(1, 1)), Some(WHITE));
+        assert_eq!(foreground(Coord::new(2, 1)), Some(WHITE));
+    }
+}
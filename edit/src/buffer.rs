@@ -0,0 +1,233 @@
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+fn is_word_character(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+pub(crate) fn previous_index(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+pub(crate) fn next_index(text: &str, index: usize) -> usize {
+    text[index..]
+        .chars()
+        .next()
+        .map_or(index, |ch| index + ch.len_utf8())
+}
+
+/// The start of the word before `index`
+pub(crate) fn word_left(text: &str, mut index: usize) -> usize {
+    let is_word_before = |index: usize| text[..index].chars().next_back().map(is_word_character);
+    while is_word_before(index) == Some(false) {
+        index = previous_index(text, index);
+    }
+    while is_word_before(index) == Some(true) {
+        index = previous_index(text, index);
+    }
+    index
+}
+
+/// The end of the word after `index`
+pub(crate) fn word_right(text: &str, mut index: usize) -> usize {
+    let is_word_after = |index: usize| text[index..].chars().next().map(is_word_character);
+    while is_word_after(index) == Some(false) {
+        index = next_index(text, index);
+    }
+    while is_word_after(index) == Some(true) {
+        index = next_index(text, index);
+    }
+    index
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+/// Text with a cursor, selection and undo stack, shared by the editors in this
+/// crate. Positions in the text are byte indices.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub(crate) struct Buffer {
+    text: String,
+    cursor: usize,
+    /// The end of the selection which doesn't move with the cursor
+    anchor: Option<usize>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// Whether the last edit was typing, which can be undone together with more typing
+    typing: bool,
+    pub(crate) max_length: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub(crate) validate: Option<fn(&str) -> bool>,
+}
+
+impl Buffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            text: String::new(),
+            cursor: 0,
+            anchor: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            typing: false,
+            max_length: None,
+            validate: None,
+        }
+    }
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+    pub(crate) fn cursor(&self) -> usize {
+        self.cursor
+    }
+    pub(crate) fn selection(&self) -> Option<Range<usize>> {
+        self.anchor.and_then(|anchor| {
+            if anchor < self.cursor {
+                Some(anchor..self.cursor)
+            } else if anchor > self.cursor {
+                Some(self.cursor..anchor)
+            } else {
+                None
+            }
+        })
+    }
+    pub(crate) fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|selection| &self.text[selection])
+    }
+    /// Replace the text, clearing the undo stack
+    pub(crate) fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.cursor = self.text.len();
+        self.anchor = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = false;
+    }
+    pub(crate) fn take_text(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.set_text(String::new());
+        text
+    }
+    /// Replace `range` of the text with `insert`, respecting the maximum length and
+    /// validator. Returns false if the text was left unchanged.
+    pub(crate) fn replace(&mut self, range: Range<usize>, insert: &str, typing: bool) -> bool {
+        let insert = match self.max_length {
+            Some(max_length) => {
+                let kept = self.text[..range.start].chars().count()
+                    + self.text[range.end..].chars().count();
+                let end = insert
+                    .char_indices()
+                    .nth(max_length.saturating_sub(kept))
+                    .map_or(insert.len(), |(i, _)| i);
+                &insert[..end]
+            }
+            None => insert,
+        };
+        if range.is_empty() && insert.is_empty() {
+            return false;
+        }
+        let mut text = String::with_capacity(self.text.len() + insert.len());
+        text.push_str(&self.text[..range.start]);
+        text.push_str(insert);
+        text.push_str(&self.text[range.end..]);
+        if let Some(validate) = self.validate {
+            if !validate(&text) {
+                return false;
+            }
+        }
+        if !(typing && self.typing) {
+            self.undo.push(Snapshot {
+                text: std::mem::replace(&mut self.text, text),
+                cursor: self.cursor,
+            });
+        } else {
+            self.text = text;
+        }
+        self.redo.clear();
+        self.typing = typing;
+        self.cursor = range.start + insert.len();
+        self.anchor = None;
+        true
+    }
+    /// Insert text at the cursor, replacing the selection
+    pub(crate) fn insert_str(&mut self, insert: &str) -> bool {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        self.replace(range, insert, false)
+    }
+    /// Insert a typed character at the cursor, replacing the selection. Consecutive
+    /// typed characters are undone together.
+    pub(crate) fn insert_char(&mut self, ch: char) -> bool {
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        self.replace(range, ch.encode_utf8(&mut [0; 4]), true)
+    }
+    /// Delete the selection if there is one, or else the text between the cursor
+    /// and `index`
+    pub(crate) fn delete_to(&mut self, index: usize) -> bool {
+        let range = self.selection().unwrap_or(if index < self.cursor {
+            index..self.cursor
+        } else {
+            self.cursor..index
+        });
+        self.replace(range, "", false)
+    }
+    pub(crate) fn move_to(&mut self, index: usize, select: bool) {
+        if select {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = index;
+        self.typing = false;
+    }
+    /// Move the cursor, leaving the selection where it started
+    pub(crate) fn start_selection(&mut self, index: usize) {
+        self.move_to(index, false);
+        self.anchor = Some(index);
+    }
+    pub(crate) fn clear_selection(&mut self) {
+        self.anchor = None;
+    }
+    pub(crate) fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.text.len();
+        self.typing = false;
+    }
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let current = Snapshot {
+            text: std::mem::replace(&mut self.text, snapshot.text),
+            cursor: self.cursor,
+        };
+        self.cursor = snapshot.cursor;
+        self.anchor = None;
+        self.typing = false;
+        current
+    }
+    pub(crate) fn undo(&mut self) -> bool {
+        if let Some(snapshot) = self.undo.pop() {
+            let current = self.restore(snapshot);
+            self.redo.push(current);
+            true
+        } else {
+            false
+        }
+    }
+    pub(crate) fn redo(&mut self) -> bool {
+        if let Some(snapshot) = self.redo.pop() {
+            let current = self.restore(snapshot);
+            self.undo.push(current);
+            true
+        } else {
+            false
+        }
+    }
+}
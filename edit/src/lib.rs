@@ -0,0 +1,5 @@
+mod buffer;
+mod line_editor;
+mod text_area;
+pub use line_editor::*;
+pub use text_area::*;
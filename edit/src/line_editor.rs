@@ -0,0 +1,616 @@
+use crate::buffer::{next_index, previous_index, word_left, word_right, Buffer};
+use chargrid_event_routine::{
+    event_or_peek_with_handled, EventOrPeek, EventRoutine, Handled, ViewSelector,
+};
+use chargrid_input::{keys, Input, Key, KeyModifiers, KeyboardInput, MouseButton, MouseInput};
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+/// Something a line editor can be told to do by a key binding
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineEditorAction {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    SelectLeft,
+    SelectRight,
+    SelectWordLeft,
+    SelectWordRight,
+    SelectHome,
+    SelectEnd,
+    SelectAll,
+    Backspace,
+    Delete,
+    DeleteWordLeft,
+    DeleteWordRight,
+    DeleteToHome,
+    DeleteToEnd,
+    Undo,
+    Redo,
+    HistoryPrevious,
+    HistoryNext,
+    Submit,
+    Cancel,
+}
+
+/// What happened to a line editor in response to input, if it finished editing
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineEditorEvent {
+    Submit(String),
+    Cancel,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct LineEditorStyle {
+    pub text: Style,
+    /// Applied on top of the text style for the cell under the cursor
+    pub cursor: Style,
+    /// Applied on top of the text style for selected text
+    pub selection: Style,
+    /// Keys which perform actions. Printable characters which aren't bound are
+    /// inserted into the text.
+    pub bindings: HashMap<KeyboardInput, LineEditorAction>,
+}
+
+const CTRL_SHIFT: KeyModifiers = KeyModifiers::CTRL.union(KeyModifiers::SHIFT);
+
+impl LineEditorStyle {
+    /// Bindings which follow common terminal conventions where possible
+    pub fn default_bindings() -> HashMap<KeyboardInput, LineEditorAction> {
+        use LineEditorAction::*;
+        vec![
+            (KeyboardInput::new(Key::Left), Left),
+            (KeyboardInput::new(Key::Right), Right),
+            (KeyboardInput::new(Key::Home), Home),
+            (KeyboardInput::new(Key::End), End),
+            (KeyboardInput::ctrl(Key::Left), WordLeft),
+            (KeyboardInput::ctrl(Key::Right), WordRight),
+            (KeyboardInput::alt(Key::Char('b')), WordLeft),
+            (KeyboardInput::alt(Key::Char('f')), WordRight),
+            (KeyboardInput::shift(Key::Left), SelectLeft),
+            (KeyboardInput::shift(Key::Right), SelectRight),
+            (
+                KeyboardInput::new(Key::Left).with_modifiers(CTRL_SHIFT),
+                SelectWordLeft,
+            ),
+            (
+                KeyboardInput::new(Key::Right).with_modifiers(CTRL_SHIFT),
+                SelectWordRight,
+            ),
+            (KeyboardInput::shift(Key::Home), SelectHome),
+            (KeyboardInput::shift(Key::End), SelectEnd),
+            (KeyboardInput::ctrl(Key::Char('a')), SelectAll),
+            (keys::BACKSPACE, Backspace),
+            (KeyboardInput::new(Key::Delete), Delete),
+            (KeyboardInput::ctrl(Key::Char('w')), DeleteWordLeft),
+            (KeyboardInput::ctrl(Key::Delete), DeleteWordRight),
+            (KeyboardInput::ctrl(Key::Char('u')), DeleteToHome),
+            (KeyboardInput::ctrl(Key::Char('k')), DeleteToEnd),
+            (KeyboardInput::ctrl(Key::Char('z')), Undo),
+            (KeyboardInput::ctrl(Key::Char('y')), Redo),
+            (KeyboardInput::new(Key::Up), HistoryPrevious),
+            (KeyboardInput::new(Key::Down), HistoryNext),
+            (keys::RETURN, Submit),
+            (keys::ESCAPE, Cancel),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+impl Default for LineEditorStyle {
+    fn default() -> Self {
+        Self {
+            text: Style::new().with_foreground(Rgb24::new_grey(255)),
+            cursor: Style::new()
+                .with_foreground(Rgb24::new_grey(0))
+                .with_background(Rgb24::new_grey(255)),
+            selection: Style::new().with_background(Rgb24::new_grey(95)),
+            bindings: Self::default_bindings(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct LineEditorLimits {
+    last_rendered_offset: Coord,
+    last_rendered_size: Size,
+    /// The number of columns of text scrolled off the left of the view
+    scroll: u32,
+}
+
+impl LineEditorLimits {
+    pub fn new() -> Self {
+        Self {
+            last_rendered_offset: Coord::new(0, 0),
+            last_rendered_size: Size::new(0, 0),
+            scroll: 0,
+        }
+    }
+}
+
+impl Default for LineEditorLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The text being edited, along with the cursor, selection, undo stack and
+/// input history. Positions in the text are byte indices.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct LineEditorState {
+    buffer: Buffer,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    dragging: bool,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    /// The text being edited before moving into the history
+    draft: String,
+    mask: Option<char>,
+}
+
+impl LineEditorState {
+    pub fn new() -> Self {
+        Self {
+            buffer: Buffer::new(),
+            dragging: false,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+            mask: None,
+        }
+    }
+    /// Start with some text, with the cursor at its end
+    pub fn with_text<S: Into<String>>(mut self, text: S) -> Self {
+        self.buffer.set_text(text.into());
+        self
+    }
+    /// Limit the text to a number of characters. Insertions are cut short to fit.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.buffer.max_length = Some(max_length);
+        self
+    }
+    /// Draw every character as `mask`, e.g. for passwords
+    pub fn with_mask(self, mask: char) -> Self {
+        Self {
+            mask: Some(mask),
+            ..self
+        }
+    }
+    /// Reject any edit which would produce text for which `validate` returns false
+    pub fn with_validator(mut self, validate: fn(&str) -> bool) -> Self {
+        self.buffer.validate = Some(validate);
+        self
+    }
+    /// Previously submitted text, oldest first, which can be recalled with
+    /// `HistoryPrevious` and `HistoryNext`
+    pub fn with_history(self, history: Vec<String>) -> Self {
+        Self { history, ..self }
+    }
+    pub fn text(&self) -> &str {
+        self.buffer.text()
+    }
+    pub fn cursor(&self) -> usize {
+        self.buffer.cursor()
+    }
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+    /// The selected range of the text, if any text is selected
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.buffer.selection()
+    }
+    /// Replace the text, clearing the undo stack
+    pub fn set_text<S: Into<String>>(&mut self, text: S) {
+        self.buffer.set_text(text.into());
+    }
+    pub fn clear(&mut self) {
+        self.buffer.set_text(String::new());
+        self.history_index = None;
+    }
+    /// Insert text at the cursor, replacing the selection
+    pub fn insert_str(&mut self, insert: &str) -> bool {
+        self.buffer.insert_str(insert)
+    }
+    pub fn undo(&mut self) -> bool {
+        self.buffer.undo()
+    }
+    pub fn redo(&mut self) -> bool {
+        self.buffer.redo()
+    }
+    fn recall_history(&mut self, history_index: Option<usize>) {
+        if self.history_index.is_none() {
+            self.draft = self.buffer.text().to_string();
+        }
+        self.history_index = history_index;
+        let text = match history_index {
+            Some(i) => self.history[i].clone(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.buffer
+            .replace(0..self.buffer.text().len(), &text, false);
+    }
+    /// Add the current text to the history and clear it, returning the text
+    pub fn submit(&mut self) -> String {
+        let text = self.buffer.take_text();
+        if !text.is_empty() && self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+        }
+        self.history_index = None;
+        text
+    }
+    /// Convert a column of the text as drawn into a position in the text
+    fn index_at_column(&self, column: i32) -> usize {
+        let mut x = 0;
+        for (i, ch) in self.buffer.text().char_indices() {
+            let width = self.mask.unwrap_or(ch).width().unwrap_or(0) as i32;
+            if column < x + width {
+                return i;
+            }
+            x += width;
+        }
+        self.buffer.text().len()
+    }
+    pub fn perform(&mut self, action: LineEditorAction) -> Option<LineEditorEvent> {
+        use LineEditorAction::*;
+        let buffer = &mut self.buffer;
+        let text = buffer.text();
+        let cursor = buffer.cursor();
+        let end = text.len();
+        let selection = buffer.selection();
+        match action {
+            Left => match selection {
+                Some(selection) => buffer.move_to(selection.start, false),
+                None => buffer.move_to(previous_index(text, cursor), false),
+            },
+            Right => match selection {
+                Some(selection) => buffer.move_to(selection.end, false),
+                None => buffer.move_to(next_index(text, cursor), false),
+            },
+            WordLeft => buffer.move_to(word_left(text, cursor), false),
+            WordRight => buffer.move_to(word_right(text, cursor), false),
+            Home => buffer.move_to(0, false),
+            End => buffer.move_to(end, false),
+            SelectLeft => buffer.move_to(previous_index(text, cursor), true),
+            SelectRight => buffer.move_to(next_index(text, cursor), true),
+            SelectWordLeft => buffer.move_to(word_left(text, cursor), true),
+            SelectWordRight => buffer.move_to(word_right(text, cursor), true),
+            SelectHome => buffer.move_to(0, true),
+            SelectEnd => buffer.move_to(end, true),
+            SelectAll => buffer.select_all(),
+            Backspace => {
+                buffer.delete_to(previous_index(text, cursor));
+            }
+            Delete => {
+                buffer.delete_to(next_index(text, cursor));
+            }
+            DeleteWordLeft => {
+                buffer.delete_to(word_left(text, cursor));
+            }
+            DeleteWordRight => {
+                buffer.delete_to(word_right(text, cursor));
+            }
+            DeleteToHome => {
+                buffer.clear_selection();
+                buffer.delete_to(0);
+            }
+            DeleteToEnd => {
+                buffer.clear_selection();
+                buffer.delete_to(end);
+            }
+            Undo => {
+                buffer.undo();
+            }
+            Redo => {
+                buffer.redo();
+            }
+            HistoryPrevious => {
+                let history_index = match self.history_index {
+                    Some(i) => i.saturating_sub(1),
+                    None => self.history.len().checked_sub(1)?,
+                };
+                self.recall_history(Some(history_index));
+            }
+            HistoryNext => {
+                let i = self.history_index?;
+                let history_index = if i + 1 < self.history.len() {
+                    Some(i + 1)
+                } else {
+                    None
+                };
+                self.recall_history(history_index);
+            }
+            Submit => return Some(LineEditorEvent::Submit(self.submit())),
+            Cancel => return Some(LineEditorEvent::Cancel),
+        }
+        None
+    }
+    /// Update the editor in response to input, using the geometry recorded in
+    /// `limits` the last time the view was rendered. Returns an event if the text
+    /// was submitted or editing was cancelled.
+    pub fn on_input(
+        &mut self,
+        input: Input,
+        style: &LineEditorStyle,
+        limits: LineEditorLimits,
+    ) -> Option<LineEditorEvent> {
+        match input {
+            Input::Keyboard(keyboard_input) => {
+                if let Some(&action) = style.bindings.get(&keyboard_input) {
+                    return self.perform(action);
+                }
+                if let KeyboardInput {
+                    key: Key::Char(ch),
+                    modifiers: KeyModifiers::NONE,
+                } = keyboard_input
+                {
+                    if !ch.is_control() {
+                        self.buffer.insert_char(ch);
+                    }
+                }
+            }
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => {
+                let relative_coord = coord - limits.last_rendered_offset;
+                if relative_coord.is_valid(limits.last_rendered_size) {
+                    let index = self.index_at_column(relative_coord.x + limits.scroll as i32);
+                    self.buffer.start_selection(index);
+                    self.dragging = true;
+                }
+            }
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord,
+            }) if self.dragging => {
+                let relative_coord = coord - limits.last_rendered_offset;
+                let index = self.index_at_column(relative_coord.x + limits.scroll as i32);
+                self.buffer.move_to(index, true);
+            }
+            Input::Mouse(MouseInput::MouseMove { .. })
+            | Input::Mouse(MouseInput::MouseRelease { .. }) => self.dragging = false,
+            _ => (),
+        }
+        None
+    }
+}
+
+impl Default for LineEditorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draws the text of a line editor on a single line, scrolling horizontally to
+/// keep the cursor in view
+pub struct LineEditorView {
+    pub style: LineEditorStyle,
+    limits: LineEditorLimits,
+}
+
+impl LineEditorView {
+    pub fn new(style: LineEditorStyle) -> Self {
+        Self {
+            style,
+            limits: LineEditorLimits::new(),
+        }
+    }
+    pub fn limits(&self) -> LineEditorLimits {
+        self.limits
+    }
+}
+
+impl Default for LineEditorView {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<'a> View<&'a LineEditorState> for LineEditorView {
+    fn view<F: Frame, C: ColModify>(
+        &mut self,
+        state: &'a LineEditorState,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) {
+        self.limits.last_rendered_offset = context.offset;
+        self.limits.last_rendered_size = context.size;
+        let width = context.size.width();
+        let display_character = |ch: char| state.mask.unwrap_or(ch);
+        let column_of = |index: usize| -> u32 {
+            state.text()[..index]
+                .chars()
+                .map(|ch| display_character(ch).width().unwrap_or(0) as u32)
+                .sum()
+        };
+        let cursor_column = column_of(state.cursor());
+        // leave room for the cursor after the end of the text
+        let total_width = column_of(state.text().len()) + 1;
+        let mut scroll = self.limits.scroll;
+        if cursor_column < scroll {
+            scroll = cursor_column;
+        } else if width > 0 && cursor_column >= scroll + width {
+            scroll = cursor_column + 1 - width;
+        }
+        scroll = scroll.min(total_width.saturating_sub(width));
+        self.limits.scroll = scroll;
+        let selection = state.selection();
+        let mut x = -(scroll as i32);
+        for (i, ch) in state.text().char_indices() {
+            let ch = display_character(ch);
+            let ch_width = ch.width().unwrap_or(0) as i32;
+            if x >= 0 && x + ch_width <= width as i32 {
+                let mut style = self.style.text;
+                if selection.as_ref().is_some_and(|s| s.contains(&i)) {
+                    style = self.style.selection.coalesce(style);
+                }
+                if i == state.cursor() {
+                    style = self.style.cursor.coalesce(style);
+                }
+                let view_cell = ViewCell::new().with_character(ch).with_style(style);
+                frame.set_cell_relative(Coord::new(x, 0), 0, view_cell, context);
+            }
+            x += ch_width;
+        }
+        if state.cursor() == state.text().len() && x >= 0 && x < width as i32 {
+            let view_cell = ViewCell::new()
+                .with_character(' ')
+                .with_style(self.style.cursor.coalesce(self.style.text));
+            frame.set_cell_relative(Coord::new(x, 0), 0, view_cell, context);
+        }
+    }
+}
+
+/// An event routine which edits a line of text. It returns the text when it is
+/// submitted, or `None` if editing is cancelled.
+pub struct LineEditorRoutine<S, D> {
+    s: S,
+    data: PhantomData<D>,
+    state: LineEditorState,
+}
+
+impl<S, D> LineEditorRoutine<S, D>
+where
+    S: ViewSelector<ViewOutput = LineEditorView>,
+{
+    pub fn new(s: S, state: LineEditorState) -> Self {
+        Self {
+            s,
+            data: PhantomData,
+            state,
+        }
+    }
+}
+
+impl<S, D> EventRoutine for LineEditorRoutine<S, D>
+where
+    S: ViewSelector<ViewOutput = LineEditorView>,
+{
+    type Return = Option<String>;
+    type Data = D;
+    type View = S::ViewInput;
+    type Event = Input;
+
+    fn handle<EP>(
+        self,
+        _data: &mut Self::Data,
+        view: &Self::View,
+        event_or_peek: EP,
+    ) -> Handled<Self::Return, Self>
+    where
+        EP: EventOrPeek<Event = Self::Event>,
+    {
+        event_or_peek_with_handled(event_or_peek, self, |mut s, input| {
+            let line_editor_view = s.s.view(view);
+            match s
+                .state
+                .on_input(input, &line_editor_view.style, line_editor_view.limits)
+            {
+                Some(LineEditorEvent::Submit(text)) => Handled::Return(Some(text)),
+                Some(LineEditorEvent::Cancel) => Handled::Return(None),
+                None => Handled::Continue(s),
+            }
+        })
+    }
+
+    fn view<F, C>(
+        &self,
+        _data: &Self::Data,
+        view: &mut Self::View,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        F: Frame,
+        C: ColModify,
+    {
+        self.s.view_mut(view).view(&self.state, context, frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    fn type_keys(state: &mut LineEditorState, inputs: &[KeyboardInput]) {
+        let style = LineEditorStyle::default();
+        for &input in inputs {
+            state.on_input(Input::Keyboard(input), &style, LineEditorLimits::new());
+        }
+    }
+
+    fn chars(s: &str) -> Vec<KeyboardInput> {
+        s.chars()
+            .map(|c| KeyboardInput::new(Key::Char(c)))
+            .collect()
+    }
+
+    #[test]
+    fn editing_and_undo() {
+        use LineEditorAction::*;
+        let mut state = LineEditorState::new();
+        type_keys(&mut state, &chars("hello world"));
+        state.perform(WordLeft);
+        state.perform(DeleteWordLeft);
+        assert_eq!(state.text(), "world");
+        state.perform(End);
+        type_keys(&mut state, &chars("!"));
+        assert_eq!(state.text(), "world!");
+        state.perform(Undo);
+        assert_eq!(state.text(), "world");
+        state.perform(Undo);
+        assert_eq!(state.text(), "hello world");
+        state.perform(Undo);
+        assert_eq!(state.text(), "");
+        state.perform(Redo);
+        assert_eq!(state.text(), "hello world");
+        state.perform(SelectAll);
+        type_keys(&mut state, &chars("x"));
+        assert_eq!(state.text(), "x");
+    }
+
+    #[test]
+    fn limits_and_history() {
+        let mut state = LineEditorState::new()
+            .with_max_length(3)
+            .with_validator(|text| text.chars().all(|ch| ch.is_ascii_digit()));
+        type_keys(&mut state, &chars("1a2345"));
+        assert_eq!(state.text(), "123");
+        assert_eq!(
+            state.perform(LineEditorAction::Submit),
+            Some(LineEditorEvent::Submit("123".to_string()))
+        );
+        type_keys(&mut state, &chars("4"));
+        state.perform(LineEditorAction::HistoryPrevious);
+        assert_eq!(state.text(), "123");
+        state.perform(LineEditorAction::HistoryNext);
+        assert_eq!(state.text(), "4");
+    }
+
+    #[test]
+    fn scroll_to_cursor() {
+        let size = Size::new(4, 1);
+        let mut test_grid = TestGrid::new(size);
+        let state = LineEditorState::new().with_text("secret").with_mask('*');
+        LineEditorView::default().view(
+            &state,
+            ViewContext::default_with_size(size),
+            &mut test_grid,
+        );
+        assert_eq!(test_grid.string_rows(), &["*** "]);
+    }
+}
@@ -0,0 +1,630 @@
+use crate::buffer::{next_index, previous_index, word_left, word_right, Buffer};
+use chargrid_decorator::{
+    VerticalScrollBarStyle, VerticalScrollLimits, VerticalScrollState, VerticalScrollView,
+};
+use chargrid_input::{keys, Input, Key, KeyModifiers, KeyboardInput, MouseButton, MouseInput};
+use chargrid_render::*;
+use chargrid_text::{
+    wrap::{self, Wrap},
+    TextLayout,
+};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Somewhere to copy text to and paste text from. Implement this to connect a
+/// text area to the system clipboard.
+pub trait Clipboard {
+    fn get(&mut self) -> Option<String>;
+    fn set(&mut self, text: String);
+}
+
+/// A clipboard which only exists within the application
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct LocalClipboard {
+    text: Option<String>,
+}
+
+impl LocalClipboard {
+    pub fn new() -> Self {
+        Self { text: None }
+    }
+}
+
+impl Clipboard for LocalClipboard {
+    fn get(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+    fn set(&mut self, text: String) {
+        self.text = Some(text);
+    }
+}
+
+/// Something a text area can be told to do by a key binding
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextAreaAction {
+    Left,
+    Right,
+    /// Move to the line above as drawn, which may be part of the same wrapped line
+    Up,
+    Down,
+    WordLeft,
+    WordRight,
+    /// Move to the start of the line, ignoring soft wrapping
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    SelectLeft,
+    SelectRight,
+    SelectUp,
+    SelectDown,
+    SelectWordLeft,
+    SelectWordRight,
+    SelectHome,
+    SelectEnd,
+    SelectAll,
+    Newline,
+    Backspace,
+    Delete,
+    DeleteWordLeft,
+    DeleteWordRight,
+    Undo,
+    Redo,
+    Copy,
+    Cut,
+    Paste,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TextAreaStyle {
+    pub text: Style,
+    /// Applied on top of the text style for the cell under the cursor
+    pub cursor: Style,
+    /// Applied on top of the text style for selected text
+    pub selection: Style,
+    pub scroll_bar: VerticalScrollBarStyle,
+    /// Keys which perform actions. Printable characters and tabs which aren't bound
+    /// are inserted into the text.
+    pub bindings: HashMap<KeyboardInput, TextAreaAction>,
+}
+
+const CTRL_SHIFT: KeyModifiers = KeyModifiers::CTRL.union(KeyModifiers::SHIFT);
+
+impl TextAreaStyle {
+    /// Bindings which follow common conventions where possible
+    pub fn default_bindings() -> HashMap<KeyboardInput, TextAreaAction> {
+        use TextAreaAction::*;
+        vec![
+            (KeyboardInput::new(Key::Left), Left),
+            (KeyboardInput::new(Key::Right), Right),
+            (KeyboardInput::new(Key::Up), Up),
+            (KeyboardInput::new(Key::Down), Down),
+            (KeyboardInput::new(Key::Home), Home),
+            (KeyboardInput::new(Key::End), End),
+            (KeyboardInput::new(Key::PageUp), PageUp),
+            (KeyboardInput::new(Key::PageDown), PageDown),
+            (KeyboardInput::ctrl(Key::Left), WordLeft),
+            (KeyboardInput::ctrl(Key::Right), WordRight),
+            (KeyboardInput::alt(Key::Char('b')), WordLeft),
+            (KeyboardInput::alt(Key::Char('f')), WordRight),
+            (KeyboardInput::shift(Key::Left), SelectLeft),
+            (KeyboardInput::shift(Key::Right), SelectRight),
+            (KeyboardInput::shift(Key::Up), SelectUp),
+            (KeyboardInput::shift(Key::Down), SelectDown),
+            (
+                KeyboardInput::new(Key::Left).with_modifiers(CTRL_SHIFT),
+                SelectWordLeft,
+            ),
+            (
+                KeyboardInput::new(Key::Right).with_modifiers(CTRL_SHIFT),
+                SelectWordRight,
+            ),
+            (KeyboardInput::shift(Key::Home), SelectHome),
+            (KeyboardInput::shift(Key::End), SelectEnd),
+            (KeyboardInput::ctrl(Key::Char('a')), SelectAll),
+            (keys::RETURN, Newline),
+            (keys::BACKSPACE, Backspace),
+            (KeyboardInput::new(Key::Delete), Delete),
+            (KeyboardInput::ctrl(Key::Char('w')), DeleteWordLeft),
+            (KeyboardInput::ctrl(Key::Delete), DeleteWordRight),
+            (KeyboardInput::ctrl(Key::Char('z')), Undo),
+            (KeyboardInput::ctrl(Key::Char('y')), Redo),
+            (KeyboardInput::ctrl(Key::Char('c')), Copy),
+            (KeyboardInput::ctrl(Key::Char('x')), Cut),
+            (KeyboardInput::ctrl(Key::Char('v')), Paste),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+impl Default for TextAreaStyle {
+    fn default() -> Self {
+        Self {
+            text: Style::new().with_foreground(Rgb24::new_grey(255)),
+            cursor: Style::new()
+                .with_foreground(Rgb24::new_grey(0))
+                .with_background(Rgb24::new_grey(255)),
+            selection: Style::new().with_background(Rgb24::new_grey(95)),
+            scroll_bar: VerticalScrollBarStyle::new(),
+            bindings: Self::default_bindings(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+struct TextLimits {
+    last_rendered_offset: Coord,
+    last_rendered_size: Size,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct TextAreaLimits {
+    scroll: VerticalScrollLimits,
+    /// The area of the text, excluding the scroll bar
+    text: TextLimits,
+}
+
+impl TextAreaLimits {
+    pub fn new() -> Self {
+        Self {
+            scroll: VerticalScrollLimits::new(),
+            text: TextLimits {
+                last_rendered_offset: Coord::new(0, 0),
+                last_rendered_size: Size::new(0, 0),
+            },
+        }
+    }
+}
+
+impl Default for TextAreaLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The text being edited in a text area, along with its cursor, selection, undo
+/// stack and scroll position. Text is wrapped with a wrap strategy, which defaults
+/// to word wrapping. Positions in the text are byte indices.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TextAreaState<W = wrap::Word> {
+    buffer: Buffer,
+    wrap: W,
+    scroll: VerticalScrollState,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    dragging: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    layout: Option<TextLayout>,
+}
+
+impl TextAreaState<wrap::Word> {
+    pub fn new() -> Self {
+        Self::with_wrap(wrap::Word::new())
+    }
+}
+
+impl Default for TextAreaState<wrap::Word> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Wrap + Clone> TextAreaState<W> {
+    pub fn with_wrap(wrap: W) -> Self {
+        Self {
+            buffer: Buffer::new(),
+            wrap,
+            scroll: VerticalScrollState::new(),
+            dragging: false,
+            layout: None,
+        }
+    }
+    /// Start with some text, with the cursor at its end
+    pub fn with_text<S: Into<String>>(mut self, text: S) -> Self {
+        self.set_text(text);
+        self
+    }
+    /// Limit the text to a number of characters. Insertions are cut short to fit.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.buffer.max_length = Some(max_length);
+        self
+    }
+    /// Reject any edit which would produce text for which `validate` returns false
+    pub fn with_validator(mut self, validate: fn(&str) -> bool) -> Self {
+        self.buffer.validate = Some(validate);
+        self
+    }
+    pub fn text(&self) -> &str {
+        self.buffer.text()
+    }
+    pub fn cursor(&self) -> usize {
+        self.buffer.cursor()
+    }
+    /// The selected range of the text, if any text is selected
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.buffer.selection()
+    }
+    pub fn selected_text(&self) -> Option<&str> {
+        self.buffer.selected_text()
+    }
+    pub fn scroll_state(&self) -> VerticalScrollState {
+        self.scroll
+    }
+    /// Replace the text, clearing the undo stack
+    pub fn set_text<S: Into<String>>(&mut self, text: S) {
+        self.buffer.set_text(text.into());
+        self.layout = None;
+    }
+    /// Insert text at the cursor, replacing the selection
+    pub fn insert_str(&mut self, insert: &str) -> bool {
+        self.layout = None;
+        self.buffer.insert_str(insert)
+    }
+    fn layout(&mut self, width: u32) -> &TextLayout {
+        let text = self.buffer.text();
+        let wrap = &self.wrap;
+        match self.layout {
+            Some(ref layout) if layout.width() == width => (),
+            _ => self.layout = Some(TextLayout::new(text, wrap.clone(), width)),
+        }
+        self.layout.as_ref().unwrap()
+    }
+    /// Scroll the cursor into view after the text or cursor changes
+    fn reveal_cursor(&mut self, limits: TextAreaLimits) {
+        let cursor = self.buffer.cursor();
+        let layout = self.layout(limits.text.last_rendered_size.width());
+        let line = layout.position(cursor).y.max(0) as u32;
+        let num_lines = layout.num_lines() as u32;
+        self.scroll.reveal_line(line, num_lines, limits.scroll);
+    }
+    /// The position of the cursor after moving it `num_lines` lines up (if
+    /// negative) or down as drawn
+    fn index_lines_away(&mut self, num_lines: i32, limits: TextAreaLimits) -> usize {
+        let cursor = self.buffer.cursor();
+        let end = self.buffer.text().len();
+        let layout = self.layout(limits.text.last_rendered_size.width());
+        let position = layout.position(cursor);
+        if position.y + num_lines >= layout.num_lines() as i32 {
+            end
+        } else {
+            layout.index_at(position + Coord::new(0, num_lines))
+        }
+    }
+    pub fn perform<B: Clipboard>(
+        &mut self,
+        action: TextAreaAction,
+        limits: TextAreaLimits,
+        clipboard: &mut B,
+    ) {
+        use TextAreaAction::*;
+        let page = limits.scroll_height() as i32;
+        let vertical = match action {
+            Up | SelectUp => Some(self.index_lines_away(-1, limits)),
+            Down | SelectDown => Some(self.index_lines_away(1, limits)),
+            PageUp => Some(self.index_lines_away(-page, limits)),
+            PageDown => Some(self.index_lines_away(page, limits)),
+            _ => None,
+        };
+        let buffer = &mut self.buffer;
+        let text = buffer.text();
+        let cursor = buffer.cursor();
+        let selection = buffer.selection();
+        let line_start = text[..cursor].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[cursor..].find('\n').map_or(text.len(), |i| cursor + i);
+        let edited = match action {
+            Left => {
+                match selection {
+                    Some(selection) => buffer.move_to(selection.start, false),
+                    None => buffer.move_to(previous_index(text, cursor), false),
+                }
+                false
+            }
+            Right => {
+                match selection {
+                    Some(selection) => buffer.move_to(selection.end, false),
+                    None => buffer.move_to(next_index(text, cursor), false),
+                }
+                false
+            }
+            Up | Down | PageUp | PageDown => {
+                buffer.move_to(vertical.unwrap_or(cursor), false);
+                false
+            }
+            SelectUp | SelectDown => {
+                buffer.move_to(vertical.unwrap_or(cursor), true);
+                false
+            }
+            WordLeft => {
+                buffer.move_to(word_left(text, cursor), false);
+                false
+            }
+            WordRight => {
+                buffer.move_to(word_right(text, cursor), false);
+                false
+            }
+            Home => {
+                buffer.move_to(line_start, false);
+                false
+            }
+            End => {
+                buffer.move_to(line_end, false);
+                false
+            }
+            SelectLeft => {
+                buffer.move_to(previous_index(text, cursor), true);
+                false
+            }
+            SelectRight => {
+                buffer.move_to(next_index(text, cursor), true);
+                false
+            }
+            SelectWordLeft => {
+                buffer.move_to(word_left(text, cursor), true);
+                false
+            }
+            SelectWordRight => {
+                buffer.move_to(word_right(text, cursor), true);
+                false
+            }
+            SelectHome => {
+                buffer.move_to(line_start, true);
+                false
+            }
+            SelectEnd => {
+                buffer.move_to(line_end, true);
+                false
+            }
+            SelectAll => {
+                buffer.select_all();
+                false
+            }
+            Newline => buffer.insert_str("\n"),
+            Backspace => buffer.delete_to(previous_index(text, cursor)),
+            Delete => buffer.delete_to(next_index(text, cursor)),
+            DeleteWordLeft => buffer.delete_to(word_left(text, cursor)),
+            DeleteWordRight => buffer.delete_to(word_right(text, cursor)),
+            Undo => buffer.undo(),
+            Redo => buffer.redo(),
+            Copy => {
+                if let Some(selected_text) = buffer.selected_text() {
+                    clipboard.set(selected_text.to_string());
+                }
+                false
+            }
+            Cut => match buffer.selected_text() {
+                Some(selected_text) => {
+                    clipboard.set(selected_text.to_string());
+                    buffer.delete_to(cursor)
+                }
+                None => false,
+            },
+            Paste => match clipboard.get() {
+                Some(pasted) => buffer.insert_str(&pasted),
+                None => false,
+            },
+        };
+        if edited {
+            self.layout = None;
+        }
+        self.reveal_cursor(limits);
+    }
+    /// Update the text area in response to input, using the geometry recorded in
+    /// `limits` the last time the view was rendered. The mouse wheel and scroll
+    /// bar scroll the text, and clicking or dragging over the text moves the
+    /// cursor and selects text.
+    pub fn on_input<B: Clipboard>(
+        &mut self,
+        input: Input,
+        style: &TextAreaStyle,
+        limits: TextAreaLimits,
+        clipboard: &mut B,
+    ) {
+        self.scroll.on_input(input, limits.scroll);
+        if self.scroll.is_dragging() {
+            return;
+        }
+        let index_at_coord = |state: &mut Self, coord: Coord| {
+            let relative_coord = coord - limits.text.last_rendered_offset
+                + Coord::new(0, state.scroll.scroll_position() as i32);
+            state
+                .layout(limits.text.last_rendered_size.width())
+                .index_at(relative_coord)
+        };
+        match input {
+            Input::Keyboard(keyboard_input) => {
+                if let Some(&action) = style.bindings.get(&keyboard_input) {
+                    self.perform(action, limits, clipboard);
+                } else if let KeyboardInput {
+                    key: Key::Char(ch),
+                    modifiers: KeyModifiers::NONE,
+                } = keyboard_input
+                {
+                    if !ch.is_control() || ch == '\t' {
+                        self.buffer.insert_char(ch);
+                        self.layout = None;
+                        self.reveal_cursor(limits);
+                    }
+                }
+            }
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => {
+                let relative_coord = coord - limits.text.last_rendered_offset;
+                if relative_coord.is_valid(limits.text.last_rendered_size) {
+                    let index = index_at_coord(self, coord);
+                    self.buffer.start_selection(index);
+                    self.dragging = true;
+                }
+            }
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord,
+            }) if self.dragging => {
+                let index = index_at_coord(self, coord);
+                self.buffer.move_to(index, true);
+                self.reveal_cursor(limits);
+            }
+            Input::Mouse(MouseInput::MouseMove { .. })
+            | Input::Mouse(MouseInput::MouseRelease { .. }) => self.dragging = false,
+            _ => (),
+        }
+    }
+}
+
+impl TextAreaLimits {
+    fn scroll_height(&self) -> u32 {
+        self.text.last_rendered_size.height()
+    }
+}
+
+struct TextAreaTextView<'s, 'l, W> {
+    style: &'s TextAreaStyle,
+    limits: &'l mut TextLimits,
+    state: &'s TextAreaState<W>,
+}
+
+impl<'s, 'l, W: Wrap + Clone> View<()> for TextAreaTextView<'s, 'l, W> {
+    fn view<F: Frame, C: ColModify>(&mut self, (): (), context: ViewContext<C>, frame: &mut F) {
+        self.limits.last_rendered_offset = context.offset;
+        self.limits.last_rendered_size = context.size;
+        let width = context.size.width();
+        let layout = match self.state.layout {
+            Some(ref layout) if layout.width() == width => Cow::Borrowed(layout),
+            _ => Cow::Owned(TextLayout::new(
+                self.state.text(),
+                self.state.wrap.clone(),
+                width,
+            )),
+        };
+        let cursor = self.state.cursor();
+        let selection = self.state.selection();
+        let mut cursor_drawn = false;
+        for cell in layout.cells() {
+            let mut style = self.style.text;
+            if selection
+                .as_ref()
+                .is_some_and(|s| cell.index.is_some_and(|index| s.contains(&index)))
+            {
+                style = self.style.selection.coalesce(style);
+            }
+            if cell.index == Some(cursor) && !cursor_drawn {
+                style = self.style.cursor.coalesce(style);
+                cursor_drawn = true;
+            }
+            let view_cell = ViewCell::new()
+                .with_character(cell.character)
+                .with_style(style);
+            frame.set_cell_relative(cell.coord, 0, view_cell, context);
+        }
+        if !cursor_drawn {
+            let view_cell = ViewCell::new()
+                .with_character(' ')
+                .with_style(self.style.cursor.coalesce(self.style.text));
+            frame.set_cell_relative(layout.position(cursor), 0, view_cell, context);
+        }
+        // make sure trailing empty lines count towards the height of the content
+        frame.set_cell_relative(
+            Coord::new(0, layout.num_lines() as i32 - 1),
+            0,
+            ViewCell::new(),
+            context,
+        );
+    }
+}
+
+/// Draws the text of a text area, with a scroll bar on the right
+pub struct TextAreaView {
+    pub style: TextAreaStyle,
+    limits: TextAreaLimits,
+}
+
+impl TextAreaView {
+    pub fn new(style: TextAreaStyle) -> Self {
+        Self {
+            style,
+            limits: TextAreaLimits::new(),
+        }
+    }
+    pub fn limits(&self) -> TextAreaLimits {
+        self.limits
+    }
+}
+
+impl Default for TextAreaView {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<'a, W: Wrap + Clone> View<&'a TextAreaState<W>> for TextAreaView {
+    fn view<F: Frame, C: ColModify>(
+        &mut self,
+        state: &'a TextAreaState<W>,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) {
+        let TextAreaView { style, limits } = self;
+        VerticalScrollView {
+            view: TextAreaTextView {
+                style,
+                limits: &mut limits.text,
+                state,
+            },
+            scroll_bar_style: &style.scroll_bar,
+            limits: &mut limits.scroll,
+            state: state.scroll,
+        }
+        .view((), context, frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    fn render(state: &TextAreaState, view: &mut TextAreaView, size: Size) -> Vec<String> {
+        let mut test_grid = TestGrid::new(size);
+        view.view(state, ViewContext::default_with_size(size), &mut test_grid);
+        test_grid.string_rows()
+    }
+
+    #[test]
+    fn cursor_through_wrapped_lines() {
+        use TextAreaAction::*;
+        let size = Size::new(8, 3);
+        let mut view = TextAreaView::default();
+        let mut clipboard = LocalClipboard::new();
+        let mut state = TextAreaState::new().with_text("one two three\nfour");
+        assert_eq!(
+            render(&state, &mut view, size),
+            &["one    █", "two    █", "three   "]
+        );
+        state.perform(Home, view.limits(), &mut clipboard);
+        state.perform(Up, view.limits(), &mut clipboard);
+        state.perform(Up, view.limits(), &mut clipboard);
+        assert_eq!(&state.text()[state.cursor()..], "two three\nfour");
+        state.perform(Down, view.limits(), &mut clipboard);
+        state.perform(Down, view.limits(), &mut clipboard);
+        state.perform(SelectEnd, view.limits(), &mut clipboard);
+        assert_eq!(state.selected_text(), Some("four"));
+        state.perform(Cut, view.limits(), &mut clipboard);
+        state.perform(Up, view.limits(), &mut clipboard);
+        state.perform(Paste, view.limits(), &mut clipboard);
+        assert_eq!(state.text(), "one two fourthree\n");
+        assert_eq!(
+            render(&state, &mut view, size),
+            &["two     ", "fourth █", "ree     "]
+        );
+    }
+}
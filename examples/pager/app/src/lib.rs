@@ -1,7 +1,6 @@
 use chargrid::app;
 use chargrid::decorator::*;
-use chargrid::input::*;
-use chargrid::input::{keys, Input, KeyboardInput};
+use chargrid::input::{keys, Input, Key, KeyboardInput};
 use chargrid::render::*;
 use chargrid::text::*;
 
@@ -61,38 +60,45 @@ impl AppData {
             match input {
                 Input::Keyboard(keys::ETX)
                 | Input::Keyboard(keys::ESCAPE)
-                | Input::Keyboard(KeyboardInput::Char('q')) => {
+                | Input::Keyboard(KeyboardInput {
+                    key: Key::Char('q'),
+                    ..
+                }) => {
                     return Some(app::ControlFlow::Exit);
                 }
-                Input::Mouse(MouseInput::MouseScroll { direction, .. }) => match direction {
-                    ScrollDirection::Up => self
-                        .vertical_scroll_state
-                        .scroll_up_line(view.vertical_scroll_limits),
-                    ScrollDirection::Down => self
-                        .vertical_scroll_state
-                        .scroll_down_line(view.vertical_scroll_limits),
-                    _ => (),
-                },
-                Input::Keyboard(KeyboardInput::Up) => self
+                Input::Mouse(_) => self
+                    .vertical_scroll_state
+                    .on_input(input, view.vertical_scroll_limits),
+                Input::Keyboard(KeyboardInput { key: Key::Up, .. }) => self
                     .vertical_scroll_state
                     .scroll_up_line(view.vertical_scroll_limits),
-                Input::Keyboard(KeyboardInput::Down) => self
+                Input::Keyboard(KeyboardInput { key: Key::Down, .. }) => self
                     .vertical_scroll_state
                     .scroll_down_line(view.vertical_scroll_limits),
-                Input::Keyboard(KeyboardInput::PageUp) => self
+                Input::Keyboard(KeyboardInput {
+                    key: Key::PageUp, ..
+                }) => self
                     .vertical_scroll_state
                     .scroll_up_page(view.vertical_scroll_limits),
-                Input::Keyboard(KeyboardInput::PageDown) => self
+                Input::Keyboard(KeyboardInput {
+                    key: Key::PageDown, ..
+                }) => self
                     .vertical_scroll_state
                     .scroll_down_page(view.vertical_scroll_limits),
-                Input::Keyboard(KeyboardInput::Home)
-                | Input::Keyboard(KeyboardInput::Char('g')) => self
+                Input::Keyboard(KeyboardInput { key: Key::Home, .. })
+                | Input::Keyboard(KeyboardInput {
+                    key: Key::Char('g'),
+                    ..
+                }) => self
                     .vertical_scroll_state
                     .scroll_to_top(view.vertical_scroll_limits),
-                Input::Keyboard(KeyboardInput::End) | Input::Keyboard(KeyboardInput::Char('G')) => {
-                    self.vertical_scroll_state
-                        .scroll_to_bottom(view.vertical_scroll_limits)
-                }
+                Input::Keyboard(KeyboardInput { key: Key::End, .. })
+                | Input::Keyboard(KeyboardInput {
+                    key: Key::Char('G'),
+                    ..
+                }) => self
+                    .vertical_scroll_state
+                    .scroll_to_bottom(view.vertical_scroll_limits),
                 _ => (),
             }
         }
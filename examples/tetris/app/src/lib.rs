@@ -1,6 +1,6 @@
 use chargrid::app;
 use chargrid::decorator::*;
-use chargrid::input::{keys, Input, KeyboardInput};
+use chargrid::input::{keys, Input, Key, KeyboardInput};
 use chargrid::menu::*;
 use chargrid::render::*;
 use chargrid::text::*;
@@ -223,18 +223,18 @@ impl AppData {
                         Input::Keyboard(keys::ESCAPE) => {
                             self.state = AppState::Menu;
                         }
-                        Input::Keyboard(KeyboardInput::Up) => {
+                        Input::Keyboard(KeyboardInput { key: Key::Up, .. }) => {
                             self.input_buffer.push_back(TetrisInput::Up)
                         }
-                        Input::Keyboard(KeyboardInput::Down) => {
+                        Input::Keyboard(KeyboardInput { key: Key::Down, .. }) => {
                             self.input_buffer.push_back(TetrisInput::Down)
                         }
-                        Input::Keyboard(KeyboardInput::Left) => {
+                        Input::Keyboard(KeyboardInput { key: Key::Left, .. }) => {
                             self.input_buffer.push_back(TetrisInput::Left)
                         }
-                        Input::Keyboard(KeyboardInput::Right) => {
-                            self.input_buffer.push_back(TetrisInput::Right)
-                        }
+                        Input::Keyboard(KeyboardInput {
+                            key: Key::Right, ..
+                        }) => self.input_buffer.push_back(TetrisInput::Right),
                         _ => (),
                     }
                 }
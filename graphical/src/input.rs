@@ -1,7 +1,7 @@
 use crate::Dimensions;
 use chargrid_input::{
-    keys, Input, KeyboardInput, MouseButton as ChargridMouseButton, MouseButton, MouseInput,
-    ScrollDirection,
+    keys, Input, Key, KeyModifiers, KeyboardInput, MouseButton as ChargridMouseButton, MouseButton,
+    MouseInput, ScrollDirection,
 };
 use chargrid_render::Coord;
 use winit::dpi::{LogicalPosition, PhysicalSize};
@@ -17,14 +17,14 @@ pub enum Event {
 
 macro_rules! convert_char_shift {
     ($lower:expr, $upper:expr, $shift:expr) => {
-        KeyboardInput::Char(if $shift { $upper } else { $lower })
+        Key::Char(if $shift { $upper } else { $lower })
     };
 }
 
 #[allow(clippy::cognitive_complexity)]
-fn convert_keycode_keyboard_input(code: VirtualKeyCode, shift: bool) -> Option<KeyboardInput> {
-    let keyboard_input = match code {
-        VirtualKeyCode::Space => KeyboardInput::Char(' '),
+fn convert_keycode_key(code: VirtualKeyCode, shift: bool) -> Option<Key> {
+    let key = match code {
+        VirtualKeyCode::Space => Key::Char(' '),
         VirtualKeyCode::A => convert_char_shift!('a', 'A', shift),
         VirtualKeyCode::B => convert_char_shift!('b', 'B', shift),
         VirtualKeyCode::C => convert_char_shift!('c', 'C', shift),
@@ -52,7 +52,7 @@ fn convert_keycode_keyboard_input(code: VirtualKeyCode, shift: bool) -> Option<K
         VirtualKeyCode::Y => convert_char_shift!('y', 'Y', shift),
         VirtualKeyCode::Z => convert_char_shift!('z', 'Z', shift),
         VirtualKeyCode::Key1 => convert_char_shift!('1', '!', shift),
-        VirtualKeyCode::Key2 => KeyboardInput::Char('2'),
+        VirtualKeyCode::Key2 => Key::Char('2'),
         VirtualKeyCode::Key3 => convert_char_shift!('3', '#', shift),
         VirtualKeyCode::Key4 => convert_char_shift!('4', '$', shift),
         VirtualKeyCode::Key5 => convert_char_shift!('5', '%', shift),
@@ -61,72 +61,97 @@ fn convert_keycode_keyboard_input(code: VirtualKeyCode, shift: bool) -> Option<K
         VirtualKeyCode::Key8 => convert_char_shift!('8', '*', shift),
         VirtualKeyCode::Key9 => convert_char_shift!('9', '(', shift),
         VirtualKeyCode::Key0 => convert_char_shift!('0', ')', shift),
-        VirtualKeyCode::Numpad1 => KeyboardInput::Char('1'),
-        VirtualKeyCode::Numpad2 => KeyboardInput::Char('2'),
-        VirtualKeyCode::Numpad3 => KeyboardInput::Char('3'),
-        VirtualKeyCode::Numpad4 => KeyboardInput::Char('4'),
-        VirtualKeyCode::Numpad5 => KeyboardInput::Char('5'),
-        VirtualKeyCode::Numpad6 => KeyboardInput::Char('6'),
-        VirtualKeyCode::Numpad7 => KeyboardInput::Char('7'),
-        VirtualKeyCode::Numpad8 => KeyboardInput::Char('8'),
-        VirtualKeyCode::Numpad9 => KeyboardInput::Char('9'),
-        VirtualKeyCode::Numpad0 => KeyboardInput::Char('0'),
-        VirtualKeyCode::Left => KeyboardInput::Left,
-        VirtualKeyCode::Right => KeyboardInput::Right,
-        VirtualKeyCode::Up => KeyboardInput::Up,
-        VirtualKeyCode::Down => KeyboardInput::Down,
-        VirtualKeyCode::Escape => keys::ESCAPE,
-        VirtualKeyCode::Return => keys::RETURN,
-        VirtualKeyCode::At => KeyboardInput::Char('@'),
-        VirtualKeyCode::Plus => KeyboardInput::Char('+'),
-        VirtualKeyCode::Minus => KeyboardInput::Char('-'),
+        VirtualKeyCode::Numpad1 => Key::Char('1'),
+        VirtualKeyCode::Numpad2 => Key::Char('2'),
+        VirtualKeyCode::Numpad3 => Key::Char('3'),
+        VirtualKeyCode::Numpad4 => Key::Char('4'),
+        VirtualKeyCode::Numpad5 => Key::Char('5'),
+        VirtualKeyCode::Numpad6 => Key::Char('6'),
+        VirtualKeyCode::Numpad7 => Key::Char('7'),
+        VirtualKeyCode::Numpad8 => Key::Char('8'),
+        VirtualKeyCode::Numpad9 => Key::Char('9'),
+        VirtualKeyCode::Numpad0 => Key::Char('0'),
+        VirtualKeyCode::Left => Key::Left,
+        VirtualKeyCode::Right => Key::Right,
+        VirtualKeyCode::Up => Key::Up,
+        VirtualKeyCode::Down => Key::Down,
+        VirtualKeyCode::Escape => keys::ESCAPE.key,
+        VirtualKeyCode::Return => keys::RETURN.key,
+        VirtualKeyCode::Back => keys::BACKSPACE.key,
+        VirtualKeyCode::Tab => keys::TAB.key,
+        VirtualKeyCode::Delete => Key::Delete,
+        VirtualKeyCode::At => Key::Char('@'),
+        VirtualKeyCode::Plus => Key::Char('+'),
+        VirtualKeyCode::Minus => Key::Char('-'),
         VirtualKeyCode::Equals => convert_char_shift!('=', '+', shift),
         VirtualKeyCode::Backslash => convert_char_shift!('\\', '|', shift),
         VirtualKeyCode::Grave => convert_char_shift!('`', '~', shift),
         VirtualKeyCode::Apostrophe => convert_char_shift!('\'', '"', shift),
         VirtualKeyCode::LBracket => convert_char_shift!('[', '{', shift),
         VirtualKeyCode::RBracket => convert_char_shift!(']', '}', shift),
-        VirtualKeyCode::PageUp => KeyboardInput::PageUp,
-        VirtualKeyCode::PageDown => KeyboardInput::PageDown,
-        VirtualKeyCode::Home => KeyboardInput::Home,
-        VirtualKeyCode::End => KeyboardInput::End,
-        VirtualKeyCode::F1 => KeyboardInput::Function(1),
-        VirtualKeyCode::F2 => KeyboardInput::Function(2),
-        VirtualKeyCode::F3 => KeyboardInput::Function(3),
-        VirtualKeyCode::F4 => KeyboardInput::Function(4),
-        VirtualKeyCode::F5 => KeyboardInput::Function(5),
-        VirtualKeyCode::F6 => KeyboardInput::Function(6),
-        VirtualKeyCode::F7 => KeyboardInput::Function(7),
-        VirtualKeyCode::F8 => KeyboardInput::Function(8),
-        VirtualKeyCode::F9 => KeyboardInput::Function(9),
-        VirtualKeyCode::F10 => KeyboardInput::Function(10),
-        VirtualKeyCode::F11 => KeyboardInput::Function(11),
-        VirtualKeyCode::F12 => KeyboardInput::Function(12),
-        VirtualKeyCode::F13 => KeyboardInput::Function(13),
-        VirtualKeyCode::F14 => KeyboardInput::Function(14),
-        VirtualKeyCode::F15 => KeyboardInput::Function(15),
-        VirtualKeyCode::F16 => KeyboardInput::Function(16),
-        VirtualKeyCode::F17 => KeyboardInput::Function(17),
-        VirtualKeyCode::F18 => KeyboardInput::Function(18),
-        VirtualKeyCode::F19 => KeyboardInput::Function(19),
-        VirtualKeyCode::F20 => KeyboardInput::Function(20),
-        VirtualKeyCode::F21 => KeyboardInput::Function(21),
-        VirtualKeyCode::F22 => KeyboardInput::Function(22),
-        VirtualKeyCode::F23 => KeyboardInput::Function(23),
-        VirtualKeyCode::F24 => KeyboardInput::Function(24),
+        VirtualKeyCode::PageUp => Key::PageUp,
+        VirtualKeyCode::PageDown => Key::PageDown,
+        VirtualKeyCode::Home => Key::Home,
+        VirtualKeyCode::End => Key::End,
+        VirtualKeyCode::F1 => Key::Function(1),
+        VirtualKeyCode::F2 => Key::Function(2),
+        VirtualKeyCode::F3 => Key::Function(3),
+        VirtualKeyCode::F4 => Key::Function(4),
+        VirtualKeyCode::F5 => Key::Function(5),
+        VirtualKeyCode::F6 => Key::Function(6),
+        VirtualKeyCode::F7 => Key::Function(7),
+        VirtualKeyCode::F8 => Key::Function(8),
+        VirtualKeyCode::F9 => Key::Function(9),
+        VirtualKeyCode::F10 => Key::Function(10),
+        VirtualKeyCode::F11 => Key::Function(11),
+        VirtualKeyCode::F12 => Key::Function(12),
+        VirtualKeyCode::F13 => Key::Function(13),
+        VirtualKeyCode::F14 => Key::Function(14),
+        VirtualKeyCode::F15 => Key::Function(15),
+        VirtualKeyCode::F16 => Key::Function(16),
+        VirtualKeyCode::F17 => Key::Function(17),
+        VirtualKeyCode::F18 => Key::Function(18),
+        VirtualKeyCode::F19 => Key::Function(19),
+        VirtualKeyCode::F20 => Key::Function(20),
+        VirtualKeyCode::F21 => Key::Function(21),
+        VirtualKeyCode::F22 => Key::Function(22),
+        VirtualKeyCode::F23 => Key::Function(23),
+        VirtualKeyCode::F24 => Key::Function(24),
         _ => return None,
     };
-    Some(keyboard_input)
+    Some(key)
+}
+
+fn convert_modifiers(key: Key, keymod: ModifiersState) -> KeyModifiers {
+    let mut modifiers = KeyModifiers::NONE;
+    if keymod.shift() && !key.is_printable() {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if keymod.ctrl() {
+        modifiers |= KeyModifiers::CTRL;
+    }
+    if keymod.alt() {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if keymod.logo() {
+        modifiers |= KeyModifiers::SUPER;
+    }
+    modifiers
 }
 
 fn convert_keycode(code: VirtualKeyCode, keymod: ModifiersState) -> Option<Input> {
-    convert_keycode_keyboard_input(code, keymod.shift()).map(Input::Keyboard)
+    convert_keycode_key(code, keymod.shift()).map(|key| {
+        Input::Keyboard(KeyboardInput::new(key).with_modifiers(convert_modifiers(key, keymod)))
+    })
 }
 
-fn convert_char(ch: char) -> Option<Event> {
+fn convert_char(ch: char, keymod: ModifiersState) -> Option<Event> {
     match ch {
         '>' | '.' | ',' | '<' | '/' | '?' => {
-            Some(Event::Input(Input::Keyboard(KeyboardInput::Char(ch))))
+            let key = Key::Char(ch);
+            let keyboard_input =
+                KeyboardInput::new(key).with_modifiers(convert_modifiers(key, keymod));
+            Some(Event::Input(Input::Keyboard(keyboard_input)))
         }
         _ => None,
     }
@@ -153,7 +178,7 @@ pub fn convert_event(
             *scale_factor = new_scale_factor;
             Some(Event::Resize(*new_inner_size))
         }
-        WindowEvent::ReceivedCharacter(ch) => convert_char(ch),
+        WindowEvent::ReceivedCharacter(ch) => convert_char(ch, modifier_state),
         WindowEvent::KeyboardInput { input, .. } => {
             if let ElementState::Pressed = input.state {
                 if let Some(virtual_keycode) = input.virtual_keycode {
@@ -23,8 +23,9 @@ pub enum MouseButton {
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct NotSupported;
 
+/// A key on the keyboard, ignoring any modifiers held at the time
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub enum KeyboardInput {
+pub enum Key {
     Char(char),
     Function(u8),
     Up,
@@ -38,6 +39,98 @@ pub enum KeyboardInput {
     Delete,
 }
 
+impl Key {
+    /// Whether shift is reflected in the key itself (e.g. shift+a is `Char('A')`)
+    /// rather than being reported as a modifier
+    pub fn is_printable(&self) -> bool {
+        match self {
+            Key::Char(c) => !c.is_control(),
+            _ => false,
+        }
+    }
+}
+
+/// Modifier keys held while a key was pressed. Combine them with `|`. Shift is not
+/// reported for printable characters, as it's already reflected in the character.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(1 << 0);
+    pub const CTRL: Self = Self(1 << 1);
+    pub const ALT: Self = Self(1 << 2);
+    pub const SUPER: Self = Self(1 << 3);
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// Whether all the modifiers in `other` are in `self`
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+    /// The modifiers in `self` which aren't in `other`
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl std::ops::BitOr for KeyModifiers {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitOrAssign for KeyModifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs);
+    }
+}
+
+/// A key press, along with the modifiers held at the time. When matching on
+/// keyboard input, use `..` to ignore the modifiers:
+/// `Input::Keyboard(KeyboardInput { key: Key::Up, .. })`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct KeyboardInput {
+    pub key: Key,
+    pub modifiers: KeyModifiers,
+}
+
+impl From<Key> for KeyboardInput {
+    fn from(key: Key) -> Self {
+        Self::new(key)
+    }
+}
+
+impl KeyboardInput {
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+    pub const fn with_modifiers(self, modifiers: KeyModifiers) -> Self {
+        Self { modifiers, ..self }
+    }
+    /// `key` with ctrl held
+    pub const fn ctrl(key: Key) -> Self {
+        Self::new(key).with_modifiers(KeyModifiers::CTRL)
+    }
+    /// `key` with shift held
+    pub const fn shift(key: Key) -> Self {
+        Self::new(key).with_modifiers(KeyModifiers::SHIFT)
+    }
+    /// `key` with alt held
+    pub const fn alt(key: Key) -> Self {
+        Self::new(key).with_modifiers(KeyModifiers::ALT)
+    }
+}
+
 #[cfg(feature = "serialize")]
 mod key_names {
     pub const UP: &str = "up";
@@ -49,35 +142,83 @@ mod key_names {
     pub const PAGE_UP: &str = "page-up";
     pub const PAGE_DOWN: &str = "page-down";
     pub const DELETE: &str = "delete";
+    pub const CTRL: &str = "ctrl-";
+    pub const ALT: &str = "alt-";
+    pub const SHIFT: &str = "shift-";
+    pub const SUPER: &str = "super-";
 }
 
 #[cfg(feature = "serialize")]
-impl KeyboardInput {
+impl Key {
     fn try_from_str(s: &str) -> Option<Self> {
         if s.chars().count() == 1 {
             let c = s.chars().next().unwrap();
-            return Some(KeyboardInput::Char(c));
+            return Some(Key::Char(c));
         }
         if s.starts_with('f') || s.starts_with('F') {
             let (_, maybe_number_str) = s.split_at(1);
             if let Ok(number) = maybe_number_str.parse::<u8>() {
-                return Some(KeyboardInput::Function(number));
+                return Some(Key::Function(number));
             }
         }
         use key_names::*;
         match s {
-            UP => Some(KeyboardInput::Up),
-            DOWN => Some(KeyboardInput::Down),
-            LEFT => Some(KeyboardInput::Left),
-            RIGHT => Some(KeyboardInput::Right),
-            HOME => Some(KeyboardInput::Home),
-            END => Some(KeyboardInput::End),
-            PAGE_UP => Some(KeyboardInput::PageUp),
-            PAGE_DOWN => Some(KeyboardInput::PageDown),
-            DELETE => Some(KeyboardInput::Delete),
+            UP => Some(Key::Up),
+            DOWN => Some(Key::Down),
+            LEFT => Some(Key::Left),
+            RIGHT => Some(Key::Right),
+            HOME => Some(Key::Home),
+            END => Some(Key::End),
+            PAGE_UP => Some(Key::PageUp),
+            PAGE_DOWN => Some(Key::PageDown),
+            DELETE => Some(Key::Delete),
             _ => None,
         }
     }
+
+    fn name(&self) -> String {
+        use key_names::*;
+        match self {
+            Key::Char(c) => c.to_string(),
+            Key::Function(n) => format!("f{}", n),
+            Key::Up => UP.to_string(),
+            Key::Down => DOWN.to_string(),
+            Key::Left => LEFT.to_string(),
+            Key::Right => RIGHT.to_string(),
+            Key::Home => HOME.to_string(),
+            Key::End => END.to_string(),
+            Key::PageUp => PAGE_UP.to_string(),
+            Key::PageDown => PAGE_DOWN.to_string(),
+            Key::Delete => DELETE.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+const MODIFIER_NAMES: &[(KeyModifiers, &str)] = &[
+    (KeyModifiers::CTRL, key_names::CTRL),
+    (KeyModifiers::ALT, key_names::ALT),
+    (KeyModifiers::SHIFT, key_names::SHIFT),
+    (KeyModifiers::SUPER, key_names::SUPER),
+];
+
+#[cfg(feature = "serialize")]
+impl KeyboardInput {
+    /// Parses descriptions like "a", "f5", "page-up" and "ctrl-shift-up"
+    fn try_from_str(mut s: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            // check for a key first so "-" and "ctrl--" are parsed correctly
+            if let Some(key) = Key::try_from_str(s) {
+                return Some(Self::new(key).with_modifiers(modifiers));
+            }
+            let &(modifier, name) = MODIFIER_NAMES
+                .iter()
+                .find(|(_, name)| s.starts_with(name))?;
+            modifiers |= modifier;
+            s = &s[name.len()..];
+        }
+    }
 }
 
 #[cfg(feature = "serialize")]
@@ -86,21 +227,17 @@ impl serde::Serialize for KeyboardInput {
     where
         S: serde::Serializer,
     {
-        use key_names::*;
-        use KeyboardInput::*;
-        match self {
-            Char(c) => serializer.serialize_char(*c),
-            Function(n) => serializer.serialize_str(&format!("f{}", n)),
-            Up => serializer.serialize_str(UP),
-            Down => serializer.serialize_str(DOWN),
-            Left => serializer.serialize_str(LEFT),
-            Right => serializer.serialize_str(RIGHT),
-            Home => serializer.serialize_str(HOME),
-            End => serializer.serialize_str(END),
-            PageUp => serializer.serialize_str(PAGE_UP),
-            PageDown => serializer.serialize_str(PAGE_DOWN),
-            Delete => serializer.serialize_str(DELETE),
+        if let (Key::Char(c), true) = (self.key, self.modifiers.is_empty()) {
+            return serializer.serialize_char(c);
         }
+        let mut s = String::new();
+        for &(modifier, name) in MODIFIER_NAMES {
+            if self.modifiers.contains(modifier) {
+                s.push_str(name);
+            }
+        }
+        s.push_str(&self.key.name());
+        serializer.serialize_str(&s)
     }
 }
 
@@ -207,49 +344,78 @@ impl Input {
 }
 
 pub mod keys {
-    use super::KeyboardInput;
+    use super::{Key, KeyboardInput};
 
-    pub const ESCAPE: KeyboardInput = KeyboardInput::Char('\u{1b}');
-    pub const ETX: KeyboardInput = KeyboardInput::Char('\u{3}');
-    pub const BACKSPACE: KeyboardInput = KeyboardInput::Char('\u{8}');
-    pub const TAB: KeyboardInput = KeyboardInput::Char('\u{9}');
-    pub const RETURN: KeyboardInput = KeyboardInput::Char('\u{d}');
+    pub const ESCAPE: KeyboardInput = KeyboardInput::new(Key::Char('\u{1b}'));
+    /// The ETX control code, which ansi terminals send for ctrl+c and graphical
+    /// frontends send when their window is closed. Frontends which can tell them
+    /// apart send ctrl+c as `KeyboardInput::ctrl(Key::Char('c'))` instead.
+    pub const ETX: KeyboardInput = KeyboardInput::new(Key::Char('\u{3}'));
+    pub const BACKSPACE: KeyboardInput = KeyboardInput::new(Key::Char('\u{8}'));
+    pub const TAB: KeyboardInput = KeyboardInput::new(Key::Char('\u{9}'));
+    pub const RETURN: KeyboardInput = KeyboardInput::new(Key::Char('\u{d}'));
 }
 
 #[cfg(feature = "serialize")]
 #[cfg(test)]
 mod serde_test {
+    use super::{Key, KeyModifiers, KeyboardInput};
+
     #[test]
     fn reversable() {
-        use super::KeyboardInput;
-        fn t(input: KeyboardInput) {
+        fn t(input: impl Into<KeyboardInput>) {
+            let input = input.into();
             let s = serde_json::to_string(&input).unwrap();
             assert_eq!(serde_json::from_str::<KeyboardInput>(&s).unwrap(), input);
         }
-        t(KeyboardInput::Up);
-        t(KeyboardInput::Down);
-        t(KeyboardInput::Left);
-        t(KeyboardInput::Right);
-        t(KeyboardInput::Home);
-        t(KeyboardInput::End);
-        t(KeyboardInput::PageUp);
-        t(KeyboardInput::PageDown);
-        t(KeyboardInput::Delete);
-        t(KeyboardInput::Function(42));
-        t(KeyboardInput::Char('a'));
-        t(KeyboardInput::Char('☃'));
+        t(Key::Up);
+        t(Key::Down);
+        t(Key::Left);
+        t(Key::Right);
+        t(Key::Home);
+        t(Key::End);
+        t(Key::PageUp);
+        t(Key::PageDown);
+        t(Key::Delete);
+        t(Key::Function(42));
+        t(Key::Char('a'));
+        t(Key::Char('☃'));
+        t(KeyboardInput::ctrl(Key::Char('-')));
+        t(super::keys::ETX);
+        t(super::keys::ESCAPE);
+        t(KeyboardInput::alt(Key::PageUp));
+        t(KeyboardInput::new(Key::Function(3))
+            .with_modifiers(KeyModifiers::SHIFT | KeyModifiers::SUPER));
+    }
+
+    #[test]
+    fn modifiers() {
+        let ctrl_shift_up =
+            KeyboardInput::new(Key::Up).with_modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT);
+        assert_eq!(
+            serde_json::to_string(&ctrl_shift_up).unwrap(),
+            "\"ctrl-shift-up\""
+        );
+        assert_eq!(
+            serde_json::from_str::<KeyboardInput>("\"shift-ctrl-up\"").unwrap(),
+            ctrl_shift_up
+        );
+        assert_eq!(
+            serde_json::from_str::<KeyboardInput>("\"ctrl--\"").unwrap(),
+            KeyboardInput::ctrl(Key::Char('-'))
+        );
+        assert!(serde_json::from_str::<KeyboardInput>("\"hyper-up\"").is_err());
     }
 
     #[test]
     fn example() {
-        use super::KeyboardInput;
         use std::collections::BTreeMap;
         let mut map = BTreeMap::new();
-        map.insert(KeyboardInput::Up, "UP");
-        map.insert(KeyboardInput::Down, "DOWN");
-        map.insert(KeyboardInput::Function(42), "F42");
-        map.insert(KeyboardInput::Char('a'), "A");
-        map.insert(KeyboardInput::Char('☃'), "SNOWMAN");
+        map.insert(KeyboardInput::new(Key::Up), "UP");
+        map.insert(KeyboardInput::new(Key::Down), "DOWN");
+        map.insert(KeyboardInput::new(Key::Function(42)), "F42");
+        map.insert(KeyboardInput::new(Key::Char('a')), "A");
+        map.insert(KeyboardInput::new(Key::Char('☃')), "SNOWMAN");
         let pretty_json_string = serde_json::to_string_pretty(&map).unwrap();
         assert_eq!(
             pretty_json_string,
@@ -5,12 +5,14 @@ use crate::{
 use chargrid_event_routine::{event_or_peek_with_handled, EventOrPeek, EventRoutine, Handled};
 use chargrid_input::Input;
 use chargrid_render::*;
-use chargrid_text::StringViewSingleLine;
+use chargrid_text::{Overflow, StringViewSingleLine};
 use std::marker::PhantomData;
+use std::time::Duration;
 
 pub struct DynamicStyleMenuInstanceView {
     mouse_tracker: MenuInstanceMouseTracker,
     buf: String,
+    overflow: Overflow,
 }
 
 impl DynamicStyleMenuInstanceView {
@@ -18,8 +20,21 @@ impl DynamicStyleMenuInstanceView {
         Self {
             buf: String::new(),
             mouse_tracker: Default::default(),
+            overflow: Overflow::Clip,
         }
     }
+    /// How entries which are wider than the menu are drawn
+    pub fn with_overflow(self, overflow: Overflow) -> Self {
+        Self { overflow, ..self }
+    }
+    pub fn overflow_mut(&mut self) -> &mut Overflow {
+        &mut self.overflow
+    }
+    /// Pass the durations of `CommonEvent::Frame` events here to advance entries
+    /// drawn with `Overflow::Marquee`
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        self.overflow.on_frame(since_last_frame);
+    }
 }
 
 impl Default for DynamicStyleMenuInstanceView {
@@ -60,7 +75,7 @@ where
                 selected: maybe_selected.is_some(),
             };
             let style = menu_entry_rich_string.render_rich_string(entry_to_render, &mut self.buf);
-            let mut view = StringViewSingleLine::new(style);
+            let mut view = StringViewSingleLine::new(style).with_overflow(self.overflow);
             let size = view.view_size(
                 &self.buf,
                 context.add_offset(Coord::new(0, i as i32)),
@@ -1,4 +1,4 @@
-use chargrid_input::{keys, Input, KeyboardInput, MouseInput, ScrollDirection};
+use chargrid_input::{keys, Input, Key, KeyModifiers, KeyboardInput, MouseInput, ScrollDirection};
 use chargrid_render::Coord;
 use chargrid_render::Size;
 #[cfg(feature = "serialize")]
@@ -170,20 +170,27 @@ impl<T: Clone> MenuInstance<T> {
         M: MenuIndexFromScreenCoord,
     {
         match input {
-            Input::Keyboard(keys::RETURN) | Input::Keyboard(KeyboardInput::Char(' ')) => {
+            Input::Keyboard(keys::RETURN)
+            | Input::Keyboard(KeyboardInput {
+                key: Key::Char(' '),
+                modifiers: KeyModifiers::NONE,
+            }) => {
                 return Some(self.selected().clone());
             }
-            Input::Keyboard(KeyboardInput::Up)
+            Input::Keyboard(KeyboardInput { key: Key::Up, .. })
             | Input::Mouse(MouseInput::MouseScroll {
                 direction: ScrollDirection::Up,
                 ..
             }) => self.up(),
-            Input::Keyboard(KeyboardInput::Down)
+            Input::Keyboard(KeyboardInput { key: Key::Down, .. })
             | Input::Mouse(MouseInput::MouseScroll {
                 direction: ScrollDirection::Down,
                 ..
             }) => self.down(),
-            Input::Keyboard(KeyboardInput::Char(c)) => {
+            Input::Keyboard(KeyboardInput {
+                key: Key::Char(c),
+                modifiers: KeyModifiers::NONE,
+            }) => {
                 if let Some(item) = self.hotkeys.get(&c).cloned() {
                     return Some(item);
                 }
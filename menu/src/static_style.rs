@@ -5,12 +5,14 @@ use crate::{
 use chargrid_event_routine::{event_or_peek_with_handled, EventOrPeek, EventRoutine, Handled};
 use chargrid_input::Input;
 use chargrid_render::*;
-use chargrid_text::StringViewSingleLine;
+use chargrid_text::{Overflow, StringViewSingleLine};
 use std::marker::PhantomData;
+use std::time::Duration;
 
 pub struct StaticStyleMenuInstanceView {
     mouse_tracker: MenuInstanceMouseTracker,
     buf: String,
+    overflow: Overflow,
     selected: Style,
     normal: Style,
 }
@@ -22,8 +24,21 @@ impl StaticStyleMenuInstanceView {
             selected,
             buf: String::new(),
             mouse_tracker: Default::default(),
+            overflow: Overflow::Clip,
         }
     }
+    /// How entries which are wider than the menu are drawn
+    pub fn with_overflow(self, overflow: Overflow) -> Self {
+        Self { overflow, ..self }
+    }
+    pub fn overflow_mut(&mut self) -> &mut Overflow {
+        &mut self.overflow
+    }
+    /// Pass the durations of `CommonEvent::Frame` events here to advance entries
+    /// drawn with `Overflow::Marquee`
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        self.overflow.on_frame(since_last_frame);
+    }
 }
 
 pub struct StaticStyleMenuInstanceModel<'a, E, S>
@@ -35,6 +50,19 @@ where
     menu_entry_string: &'a S,
 }
 
+impl<'a, E, S> StaticStyleMenuInstanceModel<'a, E, S>
+where
+    E: Clone,
+    S: MenuEntryString<Entry = E>,
+{
+    pub fn new(menu_instance: &'a MenuInstance<E>, menu_entry_string: &'a S) -> Self {
+        Self {
+            menu_instance,
+            menu_entry_string,
+        }
+    }
+}
+
 impl<'a, E, S> View<StaticStyleMenuInstanceModel<'a, E, S>> for StaticStyleMenuInstanceView
 where
     E: Clone,
@@ -63,7 +91,7 @@ where
             } else {
                 self.normal
             };
-            let mut view = StringViewSingleLine::new(style);
+            let mut view = StringViewSingleLine::new(style).with_overflow(self.overflow);
             let size = view.view_size(
                 &self.buf,
                 context.add_offset(Coord::new(0, i as i32)),
@@ -137,4 +137,22 @@ impl Frame for Buffer {
             }
         }
     }
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        coord: Coord,
+        depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+    ) {
+        if let Some(cell) = self.grid.get_mut(coord) {
+            if cell.foreground_depth <= depth {
+                let current_foreground_colour = cell.foreground_colour;
+                let blended_foreground_colour =
+                    blend.blend(current_foreground_colour, rgb24, alpha);
+                cell.foreground_colour = blended_foreground_colour;
+                cell.foreground_depth = depth;
+            }
+        }
+    }
 }
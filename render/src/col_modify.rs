@@ -35,6 +35,18 @@ impl ColModify for ColModifyDefaultForeground {
     }
 }
 
+/// Replaces the background colour, e.g. to highlight selected text
+#[derive(Clone, Copy, Debug)]
+pub struct ColModifyBackground(pub Rgb24);
+impl ColModify for ColModifyBackground {
+    fn foreground(&self, rgb24: Option<Rgb24>) -> Option<Rgb24> {
+        rgb24
+    }
+    fn background(&self, _rgb24: Option<Rgb24>) -> Option<Rgb24> {
+        Some(self.0)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct ColModifyMap<F: Fn(Rgb24) -> Rgb24 + Copy>(pub F);
 impl<F: Fn(Rgb24) -> Rgb24 + Copy> ColModify for ColModifyMap<F> {
@@ -46,6 +58,18 @@ impl<F: Fn(Rgb24) -> Rgb24 + Copy> ColModify for ColModifyMap<F> {
     }
 }
 
+/// Darkens colours by scaling each channel by `self.0 / 255`
+#[derive(Clone, Copy, Debug)]
+pub struct ColModifyDim(pub u8);
+impl ColModify for ColModifyDim {
+    fn foreground(&self, rgb24: Option<Rgb24>) -> Option<Rgb24> {
+        rgb24.map(|rgb24| rgb24.normalised_scalar_mul(self.0))
+    }
+    fn background(&self, rgb24: Option<Rgb24>) -> Option<Rgb24> {
+        rgb24.map(|rgb24| rgb24.normalised_scalar_mul(self.0))
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ColModifyIdentity;
 
@@ -53,6 +53,30 @@ fn blend_cell_background_relative_to_draw<F: ?Sized + Frame, C: ColModify, B: Bl
     }
 }
 
+fn blend_cell_foreground_relative_to_draw<F: ?Sized + Frame, C: ColModify, B: Blend>(
+    frame: &mut F,
+    relative_coord: Coord,
+    relative_depth: i8,
+    rgb24: Rgb24,
+    alpha: u8,
+    blend: B,
+    context: ViewContext<C>,
+) {
+    if relative_coord.is_valid(context.size) {
+        let absolute_coord = relative_coord + context.offset;
+        let absolute_depth = relative_depth + context.depth;
+        if let Some(modified_rgb24) = context.col_modify.foreground(Some(rgb24)) {
+            frame.blend_cell_foreground_absolute(
+                absolute_coord,
+                absolute_depth,
+                modified_rgb24,
+                alpha,
+                blend,
+            );
+        }
+    }
+}
+
 fn set_cell_relative_to_measure_size<F: ?Sized + Frame, C: ColModify>(
     frame: &mut F,
     relative_coord: Coord,
@@ -82,6 +106,23 @@ fn blend_cell_background_relative_to_measure_size<F: ?Sized + Frame, C: ColModif
     }
 }
 
+fn blend_cell_foreground_relative_to_measure_size<F: ?Sized + Frame, C: ColModify>(
+    frame: &mut F,
+    relative_coord: Coord,
+    context: ViewContext<C>,
+) {
+    if relative_coord.is_valid(context.size) {
+        let absolute_coord = relative_coord + context.offset;
+        frame.blend_cell_foreground_absolute(
+            absolute_coord,
+            0,
+            Rgb24::new(0, 0, 0),
+            0,
+            blend_mode::Replace,
+        );
+    }
+}
+
 pub trait Frame {
     fn set_cell_relative<C: ColModify>(
         &mut self,
@@ -125,6 +166,37 @@ pub trait Frame {
         alpha: u8,
         blend: B,
     );
+    fn blend_cell_foreground_relative<C: ColModify, B: Blend>(
+        &mut self,
+        relative_coord: Coord,
+        relative_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+        context: ViewContext<C>,
+    ) {
+        blend_cell_foreground_relative_to_draw(
+            self,
+            relative_coord,
+            relative_depth,
+            rgb24,
+            alpha,
+            blend,
+            context,
+        );
+    }
+    /// Frames which can't blend foreground colours may leave this unimplemented,
+    /// in which case foreground blending has no effect
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        absolute_coord: Coord,
+        absolute_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+    ) {
+        let _ = (absolute_coord, absolute_depth, rgb24, alpha, blend);
+    }
 }
 
 struct MeasureBounds {
@@ -188,6 +260,27 @@ impl Frame for MeasureBounds {
     ) {
         self.set_max(absolute_coord);
     }
+    fn blend_cell_foreground_relative<C: ColModify, B: Blend>(
+        &mut self,
+        relative_coord: Coord,
+        _relative_depth: i8,
+        _rgb24: Rgb24,
+        _alpha: u8,
+        _blend: B,
+        context: ViewContext<C>,
+    ) {
+        blend_cell_foreground_relative_to_measure_size(self, relative_coord, context);
+    }
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        absolute_coord: Coord,
+        _absolute_depth: i8,
+        _rgb24: Rgb24,
+        _alpha: u8,
+        _blend: B,
+    ) {
+        self.set_max(absolute_coord);
+    }
 }
 
 pub struct MeasureBoundsAndDraw<'a, D> {
@@ -316,6 +409,55 @@ where
             blend,
         );
     }
+    fn blend_cell_foreground_relative<C: ColModify, B: Blend>(
+        &mut self,
+        relative_coord: Coord,
+        relative_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+        context: ViewContext<C>,
+    ) {
+        self.draw.blend_cell_foreground_relative(
+            relative_coord,
+            relative_depth,
+            rgb24,
+            alpha,
+            blend,
+            context,
+        );
+        self.measure_bounds.blend_cell_foreground_relative(
+            relative_coord,
+            relative_depth,
+            rgb24,
+            alpha,
+            blend,
+            context,
+        );
+    }
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        absolute_coord: Coord,
+        absolute_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+    ) {
+        self.draw.blend_cell_foreground_absolute(
+            absolute_coord,
+            absolute_depth,
+            rgb24,
+            alpha,
+            blend,
+        );
+        self.measure_bounds.blend_cell_foreground_absolute(
+            absolute_coord,
+            absolute_depth,
+            rgb24,
+            alpha,
+            blend,
+        );
+    }
 }
 
 pub trait View<T> {
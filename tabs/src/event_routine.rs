@@ -0,0 +1,86 @@
+use crate::{TabBarView, TabsInstance};
+use chargrid_event_routine::{
+    DataSelector, Event, EventOrPeek, EventRoutine, Handled, Peek, ViewSelector,
+};
+use chargrid_input::Input;
+use chargrid_render::{ColModify, Coord, Frame, View, ViewContext};
+
+/// An event routine which draws a tab bar above the content routine `R`,
+/// switching tabs in response to input and passing all other input to `R`.
+/// The selector `S` finds the `TabsInstance` and `TabBarView` within the
+/// data and view of `R`.
+pub struct TabsRoutine<S, R> {
+    s: S,
+    routine: R,
+}
+
+impl<S, R> TabsRoutine<S, R>
+where
+    R: EventRoutine<Event = Input>,
+    S: DataSelector<DataInput = R::Data, DataOutput = TabsInstance>
+        + ViewSelector<ViewInput = R::View, ViewOutput = TabBarView>,
+{
+    pub fn new(s: S, routine: R) -> Self {
+        Self { s, routine }
+    }
+}
+
+impl<S, R> EventRoutine for TabsRoutine<S, R>
+where
+    R: EventRoutine<Event = Input>,
+    S: DataSelector<DataInput = R::Data, DataOutput = TabsInstance>
+        + ViewSelector<ViewInput = R::View, ViewOutput = TabBarView>,
+{
+    type Return = R::Return;
+    type Data = R::Data;
+    type View = R::View;
+    type Event = Input;
+
+    fn handle<EP>(
+        self,
+        data: &mut Self::Data,
+        view: &Self::View,
+        event_or_peek: EP,
+    ) -> Handled<Self::Return, Self>
+    where
+        EP: EventOrPeek<Event = Self::Event>,
+    {
+        event_or_peek.with(
+            (self, data),
+            |(s, data), input| {
+                let Self { s, routine } = s;
+                let tabs_instance = s.data_mut(data);
+                let tab_bar = s.view(view);
+                if tabs_instance.switch(tab_bar, input).is_some() {
+                    Handled::Continue(Self { s, routine })
+                } else {
+                    routine
+                        .handle(data, view, Event::new(input))
+                        .map_continue(|routine| Self { s, routine })
+                }
+            },
+            |(s, data)| {
+                let Self { s, routine } = s;
+                routine
+                    .handle(data, view, Peek::new())
+                    .map_continue(|routine| Self { s, routine })
+            },
+        )
+    }
+
+    fn view<F, C>(
+        &self,
+        data: &Self::Data,
+        view: &mut Self::View,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        F: Frame,
+        C: ColModify,
+    {
+        let tabs_instance = self.s.data(data);
+        self.s.view_mut(view).view(tabs_instance, context, frame);
+        self.routine
+            .view(data, view, context.add_offset(Coord::new(0, 1)), frame);
+    }
+}
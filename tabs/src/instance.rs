@@ -0,0 +1,335 @@
+use chargrid_input::{keys, Input, Key, KeyModifiers, KeyboardInput, MouseButton, MouseInput};
+use chargrid_render::Coord;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The keys which switch between tabs. Bindings take input before the content of
+/// the tab, so by default they all use modifiers, leaving plain keys for the
+/// content. Terminals can't distinguish ctrl-tab from tab, so alt-']' and alt-'['
+/// also move to the next and previous tab.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TabsBindings {
+    pub next: Vec<KeyboardInput>,
+    pub previous: Vec<KeyboardInput>,
+    /// If true, the keys '1' to '9' switch to the corresponding tab
+    pub number_keys: bool,
+    /// The modifiers which must be held with number keys to switch tabs. Set this
+    /// to `KeyModifiers::NONE` to switch with plain digits, which the content of
+    /// tabs then won't receive.
+    pub number_key_modifiers: KeyModifiers,
+}
+
+impl Default for TabsBindings {
+    fn default() -> Self {
+        Self {
+            next: vec![
+                KeyboardInput::ctrl(keys::TAB.key),
+                KeyboardInput::alt(Key::Char(']')),
+            ],
+            previous: vec![
+                KeyboardInput::new(keys::TAB.key)
+                    .with_modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT),
+                KeyboardInput::alt(Key::Char('[')),
+            ],
+            number_keys: true,
+            number_key_modifiers: KeyModifiers::ALT,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TabsInstance {
+    pub(crate) titles: Vec<String>,
+    pub(crate) selected_index: usize,
+    pub(crate) bindings: TabsBindings,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct InitialIndexOutOfBounds;
+
+pub trait TabIndexFromScreenCoord {
+    fn tab_index_from_screen_coord(&self, coord: Coord) -> Option<usize>;
+}
+
+#[derive(Debug)]
+pub struct TabsMouseTracker {
+    last_offset: Coord,
+    tab_spans: Vec<(i32, i32)>,
+}
+
+impl Default for TabsMouseTracker {
+    fn default() -> Self {
+        Self {
+            last_offset: Coord::new(0, 0),
+            tab_spans: Vec::new(),
+        }
+    }
+}
+
+impl TabsMouseTracker {
+    pub fn new_frame(&mut self, context_offset: Coord) {
+        self.last_offset = context_offset;
+        self.tab_spans.clear();
+    }
+    pub fn on_tab_view(&mut self, x: i32, width: u32) {
+        self.tab_spans.push((x, x + width as i32));
+    }
+    pub fn tab_index_from_screen_coord(&self, coord: Coord) -> Option<usize> {
+        let rel_coord = coord - self.last_offset;
+        if rel_coord.y != 0 {
+            return None;
+        }
+        self.tab_spans
+            .iter()
+            .position(|&(start, end)| rel_coord.x >= start && rel_coord.x < end)
+    }
+}
+
+pub struct TabsInstanceBuilder {
+    pub titles: Vec<String>,
+    pub selected_index: usize,
+    pub bindings: Option<TabsBindings>,
+}
+
+impl TabsInstanceBuilder {
+    pub fn build(self) -> Result<TabsInstance, InitialIndexOutOfBounds> {
+        let Self {
+            titles,
+            selected_index,
+            bindings,
+        } = self;
+        if selected_index >= titles.len() {
+            return Err(InitialIndexOutOfBounds);
+        }
+        Ok(TabsInstance {
+            titles,
+            selected_index,
+            bindings: bindings.unwrap_or_default(),
+        })
+    }
+}
+
+impl TabsInstance {
+    pub fn len(&self) -> usize {
+        self.titles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.titles.is_empty()
+    }
+
+    pub fn new<S: Into<String>>(titles: Vec<S>) -> Result<Self, InitialIndexOutOfBounds> {
+        TabsInstanceBuilder {
+            titles: titles.into_iter().map(Into::into).collect(),
+            selected_index: 0,
+            bindings: None,
+        }
+        .build()
+    }
+
+    pub fn previous(&mut self) {
+        match self.selected_index.checked_sub(1) {
+            Some(index) => self.selected_index = index,
+            None => self.selected_index = self.titles.len() - 1,
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.selected_index < self.titles.len() - 1 {
+            self.selected_index += 1;
+        } else {
+            self.selected_index = 0;
+        }
+    }
+
+    pub fn set_index(&mut self, index: usize) {
+        if index < self.titles.len() {
+            self.selected_index = index;
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_title(&self) -> &str {
+        self.titles[self.selected_index].as_str()
+    }
+
+    pub fn titles(&self) -> impl Iterator<Item = &str> {
+        self.titles.iter().map(String::as_str)
+    }
+
+    pub fn bindings(&self) -> &TabsBindings {
+        &self.bindings
+    }
+
+    pub fn bindings_mut(&mut self) -> &mut TabsBindings {
+        &mut self.bindings
+    }
+
+    /// Switch tabs in response to an input. Returns the index of the newly-selected tab
+    /// if the input was used to switch tabs, and `None` if the input should be passed on
+    /// to the content of the tab.
+    pub fn switch<M>(&mut self, view: &M, input: Input) -> Option<usize>
+    where
+        M: TabIndexFromScreenCoord,
+    {
+        match input {
+            Input::Keyboard(keyboard_input) => {
+                if self.bindings.next.contains(&keyboard_input) {
+                    self.next();
+                    return Some(self.selected_index);
+                }
+                if self.bindings.previous.contains(&keyboard_input) {
+                    self.previous();
+                    return Some(self.selected_index);
+                }
+                if let KeyboardInput {
+                    key: Key::Char(ch @ '1'..='9'),
+                    modifiers,
+                } = keyboard_input
+                {
+                    let index = ch as usize - '1' as usize;
+                    if self.bindings.number_keys
+                        && modifiers == self.bindings.number_key_modifiers
+                        && index < self.titles.len()
+                    {
+                        self.selected_index = index;
+                        return Some(self.selected_index);
+                    }
+                }
+            }
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => {
+                if let Some(index) = view.tab_index_from_screen_coord(coord) {
+                    self.set_index(index);
+                    return Some(self.selected_index);
+                }
+            }
+            #[cfg(feature = "gamepad")]
+            Input::Gamepad(gamepad_input) => {
+                use chargrid_input::GamepadButton;
+                match gamepad_input.button {
+                    GamepadButton::LeftBumper => {
+                        self.previous();
+                        return Some(self.selected_index);
+                    }
+                    GamepadButton::RightBumper => {
+                        self.next();
+                        return Some(self.selected_index);
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct NoTabBar;
+
+    impl TabIndexFromScreenCoord for NoTabBar {
+        fn tab_index_from_screen_coord(&self, _coord: Coord) -> Option<usize> {
+            None
+        }
+    }
+
+    fn key(keyboard_input: KeyboardInput) -> Input {
+        Input::Keyboard(keyboard_input)
+    }
+
+    #[test]
+    fn plain_keys_are_passed_to_content() {
+        let mut tabs = TabsInstance::new(vec!["a", "b", "c"]).unwrap();
+        for &input in &[
+            keys::TAB,
+            KeyboardInput::new(Key::Char('2')),
+            KeyboardInput::new(Key::Char(']')),
+            KeyboardInput::new(Key::Char('[')),
+        ] {
+            assert_eq!(tabs.switch(&NoTabBar, key(input)), None);
+        }
+        assert_eq!(tabs.index(), 0);
+    }
+
+    #[test]
+    fn switch_with_modifiers() {
+        let mut tabs = TabsInstance::new(vec!["a", "b", "c"]).unwrap();
+        let ctrl_tab = KeyboardInput::ctrl(keys::TAB.key);
+        let ctrl_shift_tab = KeyboardInput::new(keys::TAB.key)
+            .with_modifiers(KeyModifiers::CTRL | KeyModifiers::SHIFT);
+        assert_eq!(tabs.switch(&NoTabBar, key(ctrl_tab)), Some(1));
+        assert_eq!(tabs.switch(&NoTabBar, key(ctrl_shift_tab)), Some(0));
+        assert_eq!(tabs.switch(&NoTabBar, key(ctrl_shift_tab)), Some(2));
+        let alt_1 = KeyboardInput::alt(Key::Char('1'));
+        assert_eq!(tabs.switch(&NoTabBar, key(alt_1)), Some(0));
+        let alt_close = KeyboardInput::alt(Key::Char(']'));
+        assert_eq!(tabs.switch(&NoTabBar, key(alt_close)), Some(1));
+        tabs.bindings_mut().number_keys = false;
+        let alt_3 = KeyboardInput::alt(Key::Char('3'));
+        assert_eq!(tabs.switch(&NoTabBar, key(alt_3)), None);
+    }
+
+    #[test]
+    fn switch_with_plain_number_keys() {
+        let mut tabs = TabsInstance::new(vec!["a", "b", "c"]).unwrap();
+        tabs.bindings_mut().number_key_modifiers = KeyModifiers::NONE;
+        let plain_3 = KeyboardInput::new(Key::Char('3'));
+        assert_eq!(tabs.switch(&NoTabBar, key(plain_3)), Some(2));
+        let alt_1 = KeyboardInput::alt(Key::Char('1'));
+        assert_eq!(tabs.switch(&NoTabBar, key(alt_1)), None);
+        let plain_4 = KeyboardInput::new(Key::Char('4'));
+        assert_eq!(tabs.switch(&NoTabBar, key(plain_4)), None);
+    }
+
+    #[test]
+    fn switch_by_clicking_titles() {
+        use crate::TabBarView;
+        use chargrid_render::{Size, View, ViewContext};
+        let mut tabs = TabsInstance::new(vec!["a", "bb", "c"]).unwrap();
+        let mut view = TabBarView::default();
+        let size = Size::new(12, 1);
+        let mut test_grid = chargrid_test_grid::TestGrid::new(size);
+        let context = ViewContext::default_with_size(size).add_offset(Coord::new(1, 0));
+        view.view(&tabs, context, &mut test_grid);
+        assert_eq!(test_grid.string_rows(), &["  a │ bb │ c"]);
+        let click = |x| {
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord: Coord::new(x, 0),
+            })
+        };
+        assert_eq!(tabs.switch(&view, click(6)), Some(1));
+        assert_eq!(tabs.switch(&view, click(11)), Some(2));
+        assert_eq!(tabs.switch(&view, click(9)), None);
+        assert_eq!(tabs.switch(&view, click(0)), None);
+        assert_eq!(tabs.index(), 2);
+    }
+
+    #[cfg(feature = "gamepad")]
+    #[test]
+    fn switch_with_gamepad_bumpers() {
+        use chargrid_input::{GamepadButton, GamepadInput};
+        let mut tabs = TabsInstance::new(vec!["a", "b", "c"]).unwrap();
+        let bumper = |button| Input::Gamepad(GamepadInput { button, id: 0 });
+        assert_eq!(
+            tabs.switch(&NoTabBar, bumper(GamepadButton::LeftBumper)),
+            Some(2)
+        );
+        assert_eq!(
+            tabs.switch(&NoTabBar, bumper(GamepadButton::RightBumper)),
+            Some(0)
+        );
+        assert_eq!(tabs.switch(&NoTabBar, bumper(GamepadButton::South)), None);
+    }
+}
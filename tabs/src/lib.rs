@@ -0,0 +1,8 @@
+mod event_routine;
+pub use event_routine::*;
+
+mod instance;
+pub use instance::*;
+
+mod view;
+pub use view::*;
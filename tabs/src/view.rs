@@ -0,0 +1,140 @@
+use crate::{TabIndexFromScreenCoord, TabsInstance, TabsMouseTracker};
+use chargrid_render::*;
+use chargrid_text::StringViewSingleLine;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct TabsStyle {
+    pub normal: Style,
+    pub selected: Style,
+    /// Drawn between adjacent tab titles
+    pub separator: char,
+    pub separator_style: Style,
+    /// The number of cells on either side of each title
+    pub padding: u32,
+}
+
+impl TabsStyle {
+    pub fn new(normal: Style, selected: Style) -> Self {
+        Self {
+            normal,
+            selected,
+            separator: '│',
+            separator_style: normal,
+            padding: 1,
+        }
+    }
+}
+
+impl Default for TabsStyle {
+    fn default() -> Self {
+        Self::new(
+            Style::new().with_foreground(Rgb24::new_grey(187)),
+            Style::new()
+                .with_bold(true)
+                .with_foreground(Rgb24::new_grey(0))
+                .with_background(Rgb24::new_grey(255)),
+        )
+    }
+}
+
+/// Renders the row of tab titles, and remembers where each one was
+/// drawn so tabs can be selected with the mouse.
+pub struct TabBarView {
+    mouse_tracker: TabsMouseTracker,
+    pub style: TabsStyle,
+}
+
+impl TabBarView {
+    pub fn new(style: TabsStyle) -> Self {
+        Self {
+            mouse_tracker: Default::default(),
+            style,
+        }
+    }
+}
+
+impl Default for TabBarView {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<'a> View<&'a TabsInstance> for TabBarView {
+    fn view<F: Frame, C: ColModify>(
+        &mut self,
+        tabs_instance: &'a TabsInstance,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) {
+        self.mouse_tracker.new_frame(context.offset);
+        let padding = self.style.padding as i32;
+        let mut x = 0;
+        for (i, title) in tabs_instance.titles().enumerate() {
+            if i != 0 {
+                frame.set_cell_relative(
+                    Coord::new(x, 0),
+                    0,
+                    ViewCell::new()
+                        .with_character(self.style.separator)
+                        .with_style(self.style.separator_style),
+                    context,
+                );
+                x += 1;
+            }
+            let style = if i == tabs_instance.index() {
+                self.style.selected
+            } else {
+                self.style.normal
+            };
+            let title_size = StringViewSingleLine::new(style).view_size(
+                title,
+                context.add_offset(Coord::new(x + padding, 0)),
+                frame,
+            );
+            let title_width = if title.is_empty() {
+                0
+            } else {
+                title_size.width()
+            };
+            let width = title_width + 2 * self.style.padding;
+            for offset in (0..padding).chain((padding + title_width as i32)..(width as i32)) {
+                frame.set_cell_relative(
+                    Coord::new(x + offset, 0),
+                    0,
+                    ViewCell::new().with_character(' ').with_style(style),
+                    context,
+                );
+            }
+            self.mouse_tracker.on_tab_view(x, width);
+            x += width as i32;
+        }
+    }
+}
+
+impl TabIndexFromScreenCoord for TabBarView {
+    fn tab_index_from_screen_coord(&self, coord: Coord) -> Option<usize> {
+        self.mouse_tracker.tab_index_from_screen_coord(coord)
+    }
+}
+
+/// Renders a tab bar above some content. The data is passed to the content,
+/// which can find out which tab is selected with `TabsInstance::index`.
+pub struct TabsView<'i, 'b, V> {
+    pub view: V,
+    pub tabs_instance: &'i TabsInstance,
+    pub tab_bar: &'b mut TabBarView,
+}
+
+impl<'i, 'b, V, T> View<T> for TabsView<'i, 'b, V>
+where
+    V: View<T>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, data: T, context: ViewContext<C>, frame: &mut F) {
+        self.tab_bar.view(self.tabs_instance, context, frame);
+        self.view
+            .view(data, context.add_offset(Coord::new(0, 1)), frame);
+    }
+}
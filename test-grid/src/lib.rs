@@ -40,6 +40,10 @@ impl TestGrid {
         }
         rows
     }
+    /// The cell most recently drawn at `coord`, if any
+    pub fn view_cell(&self, coord: Coord) -> Option<ViewCell> {
+        self.grid.get(coord).and_then(|cell| cell.view_cell)
+    }
 }
 
 impl Frame for TestGrid {
@@ -65,4 +69,13 @@ impl Frame for TestGrid {
         _blend: B,
     ) {
     }
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        _absolute_coord: Coord,
+        _absolute_depth: i8,
+        _rgb24: Rgb24,
+        _alpha: u8,
+        _blend: B,
+    ) {
+    }
 }
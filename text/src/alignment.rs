@@ -0,0 +1,15 @@
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The horizontal alignment of each line of wrapped text within the context.
+/// Justified text is stretched to fill the width of the context by widening the
+/// gaps between words, except on the last line of each paragraph.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Centre,
+    Right,
+    Justify,
+}
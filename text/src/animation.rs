@@ -0,0 +1,279 @@
+use crate::wrap::{Wrap, WrapSink};
+use crate::RichTextPart;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Movement applied to each character of animated text
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEffect {
+    #[default]
+    None,
+    /// Characters bob up and down by up to `amplitude` rows in a wave which
+    /// repeats every `wavelength` characters and takes `period` to pass each one
+    Wave {
+        amplitude: u32,
+        wavelength: u32,
+        period: Duration,
+    },
+    /// Characters jump to a random cell up to `magnitude` cells away every `interval`
+    Shake { magnitude: u32, interval: Duration },
+}
+
+/// A well-mixed hash of `x`, used for deterministic randomness
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl TextEffect {
+    /// How far the `ordinal`th character is moved from where it would be drawn
+    /// after `elapsed` time
+    pub fn offset(&self, ordinal: usize, elapsed: Duration) -> Coord {
+        match *self {
+            TextEffect::None => Coord::new(0, 0),
+            TextEffect::Wave {
+                amplitude,
+                wavelength,
+                period,
+            } => {
+                let time = elapsed.as_secs_f64() / period.as_secs_f64().max(f64::EPSILON);
+                let position = ordinal as f64 / wavelength.max(1) as f64;
+                let phase = (time - position) * std::f64::consts::TAU;
+                Coord::new(0, (phase.sin() * amplitude as f64).round() as i32)
+            }
+            TextEffect::Shake {
+                magnitude,
+                interval,
+            } => {
+                let tick = (elapsed.as_nanos() / interval.as_nanos().max(1)) as u64;
+                let hash = mix(mix(ordinal as u64) ^ tick);
+                let range = 2 * magnitude as u64 + 1;
+                let dx = (hash % range) as i32 - magnitude as i32;
+                let dy = ((hash >> 32) % range) as i32 - magnitude as i32;
+                Coord::new(dx, dy)
+            }
+        }
+    }
+}
+
+/// The progress of text being revealed a character at a time. Pass it the
+/// durations of `CommonEvent::Frame` events, and render text with an
+/// `AnimatedTextView`. Each character fades in over `fade` after it's revealed.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextAnimationState {
+    elapsed: Duration,
+    char_delay: Duration,
+    fade: Duration,
+    skipped: bool,
+}
+
+impl Default for TextAnimationState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextAnimationState {
+    pub const fn new() -> Self {
+        Self {
+            elapsed: Duration::from_millis(0),
+            char_delay: Duration::from_millis(30),
+            fade: Duration::from_millis(0),
+            skipped: false,
+        }
+    }
+    /// The time between each character being revealed
+    pub const fn with_char_delay(self, char_delay: Duration) -> Self {
+        Self { char_delay, ..self }
+    }
+    /// The time taken for each character to fade in after it's revealed
+    pub const fn with_fade(self, fade: Duration) -> Self {
+        Self { fade, ..self }
+    }
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        self.elapsed += since_last_frame;
+    }
+    /// Reveal all the text at once. Effects keep animating.
+    pub fn skip(&mut self) {
+        self.skipped = true;
+    }
+    /// Start revealing the text again, e.g. when the text changes
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_millis(0);
+        self.skipped = false;
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+    /// How far the `ordinal`th character has faded in, from 0 when it hasn't been
+    /// revealed to 255 when it's fully visible
+    pub fn fade_progress(&self, ordinal: usize) -> u8 {
+        if self.skipped {
+            return 255;
+        }
+        let start = self.char_delay.as_nanos() * ordinal as u128;
+        let elapsed = self.elapsed.as_nanos();
+        if elapsed < start {
+            0
+        } else if self.fade.as_nanos() == 0 {
+            255
+        } else {
+            ((elapsed - start) * 255 / self.fade.as_nanos()).min(255) as u8
+        }
+    }
+    /// Whether all of `num_chars` characters have been revealed and faded in
+    pub fn is_complete(&self, num_chars: usize) -> bool {
+        num_chars == 0 || self.fade_progress(num_chars - 1) == 255
+    }
+}
+
+/// Draws rich text as it's revealed by a `TextAnimationState`. Characters are laid
+/// out as though all the text was visible, so words don't jump between lines as
+/// they're revealed. Characters fade in from `fade_from` to their foreground colour.
+pub struct AnimatedTextView<W: Wrap> {
+    pub effect: TextEffect,
+    pub fade_from: Rgb24,
+    wrap: W,
+}
+
+impl<W: Wrap> AnimatedTextView<W> {
+    pub fn new(wrap: W) -> Self {
+        Self {
+            effect: TextEffect::None,
+            fade_from: Rgb24::new_grey(0),
+            wrap,
+        }
+    }
+    pub fn with_effect(self, effect: TextEffect) -> Self {
+        Self { effect, ..self }
+    }
+    pub fn with_fade_from(self, fade_from: Rgb24) -> Self {
+        Self { fade_from, ..self }
+    }
+}
+
+/// Fades in and moves the cells drawn by the wrap strategy of an `AnimatedTextView`
+struct AnimatedSink<'a, 's, F> {
+    frame: &'a mut F,
+    state: &'s TextAnimationState,
+    effect: TextEffect,
+    fade_from: Rgb24,
+    /// The byte index of the start of each character
+    starts: Vec<usize>,
+    /// Characters added by the wrap strategy are animated with the character
+    /// drawn before them
+    last_ordinal: usize,
+}
+
+impl<'a, 's, F: Frame> WrapSink for AnimatedSink<'a, 's, F> {
+    fn set_cell<C: ColModify>(
+        &mut self,
+        coord: Coord,
+        index: Option<usize>,
+        mut view_cell: ViewCell,
+        context: ViewContext<C>,
+    ) {
+        let ordinal = index.map_or(self.last_ordinal, |index| {
+            self.starts
+                .partition_point(|&start| start <= index)
+                .saturating_sub(1)
+        });
+        self.last_ordinal = ordinal;
+        let progress = self.state.fade_progress(ordinal);
+        if progress == 0 {
+            return;
+        }
+        if progress < 255 {
+            view_cell.style.foreground = view_cell
+                .style
+                .foreground
+                .map(|foreground| self.fade_from.linear_interpolate(foreground, progress));
+        }
+        let coord = coord + self.effect.offset(ordinal, self.state.elapsed());
+        self.frame.set_cell_relative(coord, 0, view_cell, context);
+    }
+}
+
+impl<'a, 's, I, W> View<(&'s TextAnimationState, I)> for AnimatedTextView<W>
+where
+    I: IntoIterator<Item = RichTextPart<'a>>,
+    W: Wrap,
+{
+    fn view<F: Frame, C: ColModify>(
+        &mut self,
+        (state, parts): (&'s TextAnimationState, I),
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) {
+        let parts = parts.into_iter().collect::<Vec<_>>();
+        let mut starts = Vec::new();
+        let mut offset = 0;
+        for part in parts.iter() {
+            starts.extend(part.text.char_indices().map(|(i, _)| offset + i));
+            offset += part.text.len();
+        }
+        let mut sink = AnimatedSink {
+            frame,
+            state,
+            effect: self.effect,
+            fade_from: self.fade_from,
+            starts,
+            last_ordinal: 0,
+        };
+        self.wrap.clear();
+        let mut offset = 0;
+        for part in parts {
+            for (i, character) in part.text.char_indices() {
+                self.wrap
+                    .process_character(character, offset + i, part.style, context, &mut sink);
+            }
+            offset += part.text.len();
+        }
+        self.wrap.flush(context, &mut sink);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wrap;
+
+    #[test]
+    fn reveal_and_fade() {
+        let mut state = TextAnimationState::new()
+            .with_char_delay(Duration::from_millis(10))
+            .with_fade(Duration::from_millis(20));
+        let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(6, 2));
+        let context = ViewContext::default_with_size(Size::new(6, 2));
+        let style = Style::new().with_foreground(Rgb24::new_grey(255));
+        let parts = [RichTextPart::new("ab cd", style)];
+        let mut view = AnimatedTextView::new(wrap::Word::new());
+        state.on_frame(Duration::from_millis(15));
+        view.view((&state, parts.iter().cloned()), context, &mut test_grid);
+        assert_eq!(test_grid.string_rows(), &["ab    ", "      "]);
+        assert_eq!(state.fade_progress(1), 63);
+        assert!(!state.is_complete(5));
+        state.skip();
+        assert!(state.is_complete(5));
+        let wave = TextEffect::Wave {
+            amplitude: 1,
+            wavelength: 4,
+            period: Duration::from_millis(400),
+        };
+        assert_eq!(wave.offset(1, Duration::from_millis(0)), Coord::new(0, -1));
+        assert_eq!(wave.offset(1, Duration::from_millis(100)), Coord::new(0, 0));
+        let shake = TextEffect::Shake {
+            magnitude: 1,
+            interval: Duration::from_millis(50),
+        };
+        let offset = shake.offset(3, Duration::from_millis(120));
+        assert!(offset.x.abs() <= 1 && offset.y.abs() <= 1);
+        assert_eq!(offset, shake.offset(3, Duration::from_millis(140)));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::RichTextPart;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+const ESCAPE: char = '\u{1B}';
+const BELL: char = '\u{7}';
+
+/// The colours used for the 16 standard ANSI colour codes, in order from black
+/// to bright white. These are also the first 16 colours of the 256-colour palette.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnsiPalette {
+    pub colours: [Rgb24; 16],
+}
+
+impl AnsiPalette {
+    /// The palette used by xterm
+    pub const fn xterm() -> Self {
+        Self {
+            colours: [
+                Rgb24::new(0, 0, 0),
+                Rgb24::new(205, 0, 0),
+                Rgb24::new(0, 205, 0),
+                Rgb24::new(205, 205, 0),
+                Rgb24::new(0, 0, 238),
+                Rgb24::new(205, 0, 205),
+                Rgb24::new(0, 205, 205),
+                Rgb24::new(229, 229, 229),
+                Rgb24::new(127, 127, 127),
+                Rgb24::new(255, 0, 0),
+                Rgb24::new(0, 255, 0),
+                Rgb24::new(255, 255, 0),
+                Rgb24::new(92, 92, 255),
+                Rgb24::new(255, 0, 255),
+                Rgb24::new(0, 255, 255),
+                Rgb24::new(255, 255, 255),
+            ],
+        }
+    }
+
+    /// Looks up a colour in the 256-colour palette
+    pub fn colour_256(&self, index: u8) -> Rgb24 {
+        const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+        match index {
+            0..=15 => self.colours[index as usize],
+            16..=231 => {
+                let cube_index = index - 16;
+                Rgb24::new(
+                    CUBE_LEVELS[(cube_index / 36) as usize],
+                    CUBE_LEVELS[((cube_index / 6) % 6) as usize],
+                    CUBE_LEVELS[(cube_index % 6) as usize],
+                )
+            }
+            232..=255 => Rgb24::new_grey(8 + 10 * (index - 232)),
+        }
+    }
+}
+
+impl Default for AnsiPalette {
+    fn default() -> Self {
+        Self::xterm()
+    }
+}
+
+/// Reads an extended colour (the part of an SGR sequence following 38 or 48)
+fn extended_colour<I>(params: &mut I, palette: &AnsiPalette) -> Option<Rgb24>
+where
+    I: Iterator<Item = u32>,
+{
+    match params.next()? {
+        5 => Some(palette.colour_256(params.next()?.min(255) as u8)),
+        2 => {
+            let mut component = || params.next().map(|c| c.min(255) as u8);
+            let r = component()?;
+            let g = component()?;
+            let b = component()?;
+            Some(Rgb24::new(r, g, b))
+        }
+        _ => None,
+    }
+}
+
+/// Applies the parameters of a "select graphic rendition" sequence to a style.
+/// Unsupported attributes are ignored.
+fn apply_sgr(params: &str, style: Style, base: Style, palette: &AnsiPalette) -> Style {
+    let mut params = params
+        .split(';')
+        .map(|param| param.parse::<u32>().unwrap_or(0));
+    let mut style = style;
+    while let Some(param) = params.next() {
+        style = match param {
+            0 => base,
+            1 => style.with_bold(true),
+            4 => style.with_underline(true),
+            22 => Style {
+                bold: base.bold,
+                ..style
+            },
+            24 => Style {
+                underline: base.underline,
+                ..style
+            },
+            30..=37 => style.with_foreground(palette.colours[param as usize - 30]),
+            38 => match extended_colour(&mut params, palette) {
+                Some(rgb24) => style.with_foreground(rgb24),
+                None => style,
+            },
+            39 => Style {
+                foreground: base.foreground,
+                ..style
+            },
+            40..=47 => style.with_background(palette.colours[param as usize - 40]),
+            48 => match extended_colour(&mut params, palette) {
+                Some(rgb24) => style.with_background(rgb24),
+                None => style,
+            },
+            49 => Style {
+                background: base.background,
+                ..style
+            },
+            90..=97 => style.with_foreground(palette.colours[param as usize - 90 + 8]),
+            100..=107 => style.with_background(palette.colours[param as usize - 100 + 8]),
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Splits text containing ANSI escape sequences into rich text parts, starting
+/// with `style`. "Select graphic rendition" sequences setting bold, underline,
+/// and 16-colour, 256-colour or 24-bit foreground and background colours are
+/// applied to the style of the following text, and a reset restores `style`.
+/// All other escape sequences and control characters (other than newlines, tabs
+/// and carriage returns) are removed.
+pub fn parse_ansi<'a>(
+    input: &'a str,
+    style: Style,
+    palette: &AnsiPalette,
+) -> Vec<RichTextPart<'a>> {
+    let base = style;
+    let mut style = style;
+    let mut parts = Vec::new();
+    let mut text_start = 0;
+    let mut chars = input.char_indices().peekable();
+    while let Some((offset, ch)) = chars.next() {
+        if !ch.is_control() || ch == '\n' || ch == '\t' || ch == '\r' {
+            continue;
+        }
+        if offset > text_start {
+            parts.push(RichTextPart::new(&input[text_start..offset], style));
+        }
+        if ch == ESCAPE {
+            match chars.peek().copied() {
+                Some((params_start, '[')) => {
+                    chars.next();
+                    // control sequence: parameter and intermediate bytes followed by a final byte
+                    for (i, ch) in chars.by_ref() {
+                        if ('\u{40}'..='\u{7E}').contains(&ch) {
+                            if ch == 'm' {
+                                let params = &input[(params_start + 1)..i];
+                                style = apply_sgr(params, style, base, palette);
+                            }
+                            break;
+                        }
+                    }
+                }
+                Some((_, ']')) => {
+                    chars.next();
+                    // operating system command: terminated by a bell or "ESC \"
+                    while let Some((_, ch)) = chars.next() {
+                        if ch == BELL {
+                            break;
+                        }
+                        if ch == ESCAPE {
+                            if let Some((_, '\\')) = chars.peek() {
+                                chars.next();
+                            }
+                            break;
+                        }
+                    }
+                }
+                Some((_, ch)) if ('\u{20}'..='\u{2F}').contains(&ch) => {
+                    chars.next();
+                    // intermediate bytes followed by a final byte
+                    for (_, ch) in chars.by_ref() {
+                        if !('\u{20}'..='\u{2F}').contains(&ch) {
+                            break;
+                        }
+                    }
+                }
+                Some((_, ch)) if ('\u{30}'..='\u{7E}').contains(&ch) => {
+                    // a two character escape sequence
+                    chars.next();
+                }
+                // anything else, such as another escape, is parsed again
+                _ => (),
+            }
+        }
+        text_start = chars.peek().map(|&(i, _)| i).unwrap_or(input.len());
+    }
+    if text_start < input.len() {
+        parts.push(RichTextPart::new(&input[text_start..], style));
+    }
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sgr_sequences() {
+        let palette = AnsiPalette::default();
+        let input = "\u{1B}[1;31merror\u{1B}[0m: \u{1B}]0;title\u{7}x\u{1B}[38;5;196my\u{1B}[48;2;1;2;3;4mz\u{1B}[K";
+        let parts = parse_ansi(input, Style::new(), &palette)
+            .into_iter()
+            .map(|part| (part.text, part.style))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parts,
+            &[
+                (
+                    "error",
+                    Style::new()
+                        .with_bold(true)
+                        .with_foreground(Rgb24::new(205, 0, 0))
+                ),
+                (": ", Style::new()),
+                ("x", Style::new()),
+                ("y", Style::new().with_foreground(Rgb24::new(255, 0, 0))),
+                (
+                    "z",
+                    Style::new()
+                        .with_foreground(Rgb24::new(255, 0, 0))
+                        .with_background(Rgb24::new(1, 2, 3))
+                        .with_underline(true)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape_before_sequence() {
+        let palette = AnsiPalette::default();
+        let parts = parse_ansi("a\u{1B}\u{1B}[31mb\u{1B}7c", Style::new(), &palette)
+            .into_iter()
+            .map(|part| (part.text, part.style.foreground))
+            .collect::<Vec<_>>();
+        let red = palette.colours[1];
+        assert_eq!(parts, &[("a", None), ("b", Some(red)), ("c", Some(red))]);
+    }
+}
@@ -1,3 +1,5 @@
 use chargrid_render::*;
 
 pub(crate) const DEFAULT_STYLE: Style = Style::new().with_foreground(Rgb24::new(255, 255, 255));
+pub(crate) const DEFAULT_SELECTION_HIGHLIGHT: ColModifyBackground =
+    ColModifyBackground(Rgb24::new(63, 95, 159));
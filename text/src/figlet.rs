@@ -0,0 +1,412 @@
+use crate::default::*;
+use crate::TextAlignment;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::{error, fmt, str};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FigletFontError {
+    /// The input doesn't start with the "flf2a" signature
+    MissingSignature,
+    InvalidUtf8,
+    /// A value in the header line which is missing or isn't a number
+    InvalidHeader(String),
+    /// The input ended before all of the required characters were defined
+    UnexpectedEnd,
+    /// A code tag which isn't a number
+    InvalidCodeTag(String),
+}
+
+impl fmt::Display for FigletFontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FigletFontError::MissingSignature => write!(f, "missing \"flf2a\" signature"),
+            FigletFontError::InvalidUtf8 => write!(f, "font is not valid UTF-8"),
+            FigletFontError::InvalidHeader(field) => {
+                write!(f, "invalid header field \"{}\"", field)
+            }
+            FigletFontError::UnexpectedEnd => write!(f, "unexpected end of font"),
+            FigletFontError::InvalidCodeTag(tag) => write!(f, "invalid code tag \"{}\"", tag),
+        }
+    }
+}
+
+impl error::Error for FigletFontError {}
+
+/// How the letters of big text are pushed together
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FigletLayout {
+    /// Letters are drawn at the full width given by the font
+    FullWidth,
+    /// Letters are moved together until they touch
+    Fitting,
+    /// Letters are moved together until they overlap by one column, where the
+    /// characters of the later letter replace those of the earlier one. Only
+    /// FIGlet's universal smushing rule is supported.
+    Smushing,
+}
+
+const REQUIRED_CHARACTERS: &[u32] = &[196, 214, 220, 228, 246, 252, 223];
+
+/// A font for drawing text in big letters made of multiple cells, parsed from
+/// a FIGlet font file (.flf)
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct FigletFont {
+    height: u32,
+    /// Drawn as a space, but never overlapped by the fitting and smushing layouts
+    hardblank: char,
+    layout: FigletLayout,
+    glyphs: HashMap<char, Vec<Vec<char>>>,
+}
+
+fn parse_number(field: &str) -> Option<i64> {
+    let (negative, field) = match field.strip_prefix('-') {
+        Some(field) => (true, field),
+        None => (false, field),
+    };
+    let number = if let Some(hex) = field
+        .strip_prefix("0x")
+        .or_else(|| field.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if field.len() > 1 && field.starts_with('0') {
+        i64::from_str_radix(&field[1..], 8).ok()?
+    } else {
+        field.parse().ok()?
+    };
+    Some(if negative { -number } else { number })
+}
+
+impl FigletFont {
+    pub fn parse(bytes: &[u8]) -> Result<Self, FigletFontError> {
+        let text = str::from_utf8(bytes).map_err(|_| FigletFontError::InvalidUtf8)?;
+        let mut lines = text.lines();
+        let header = lines.next().ok_or(FigletFontError::MissingSignature)?;
+        let header = header
+            .strip_prefix("flf2a")
+            .ok_or(FigletFontError::MissingSignature)?;
+        let mut chars = header.chars();
+        let hardblank = chars.next().ok_or(FigletFontError::MissingSignature)?;
+        let fields = chars.as_str().split_whitespace().collect::<Vec<_>>();
+        let field = |index: usize, name: &str| {
+            fields
+                .get(index)
+                .and_then(|field| parse_number(field))
+                .ok_or_else(|| FigletFontError::InvalidHeader(name.to_string()))
+        };
+        let height = field(0, "height")?;
+        if height <= 0 {
+            return Err(FigletFontError::InvalidHeader("height".to_string()));
+        }
+        let old_layout = field(3, "old_layout")?;
+        let comment_lines = field(4, "comment_lines")?;
+        let full_layout = if fields.len() > 6 {
+            Some(field(6, "full_layout")?)
+        } else {
+            None
+        };
+        let layout = match full_layout {
+            Some(full_layout) if full_layout & 128 != 0 => FigletLayout::Smushing,
+            Some(full_layout) if full_layout & 64 != 0 => FigletLayout::Fitting,
+            Some(_) => FigletLayout::FullWidth,
+            None if old_layout > 0 => FigletLayout::Smushing,
+            None if old_layout == 0 => FigletLayout::Fitting,
+            None => FigletLayout::FullWidth,
+        };
+        for _ in 0..comment_lines {
+            lines.next().ok_or(FigletFontError::UnexpectedEnd)?;
+        }
+        let read_glyph = |lines: &mut str::Lines| {
+            (0..height)
+                .map(|_| {
+                    let line = lines.next().ok_or(FigletFontError::UnexpectedEnd)?;
+                    let line = line.trim_end();
+                    let line = match line.chars().next_back() {
+                        Some(endmark) => line.trim_end_matches(endmark),
+                        None => line,
+                    };
+                    Ok(line.chars().collect::<Vec<_>>())
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let mut glyphs = HashMap::new();
+        for code in (32..127).chain(REQUIRED_CHARACTERS.iter().cloned()) {
+            match read_glyph(&mut lines) {
+                Ok(glyph) => {
+                    glyphs.insert(char::from_u32(code).unwrap(), glyph);
+                }
+                // many fonts leave out the required non-ASCII characters
+                Err(FigletFontError::UnexpectedEnd) if code >= 127 => break,
+                Err(error) => return Err(error),
+            }
+        }
+        while let Some(tag) = lines.next() {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                continue;
+            }
+            let code = tag.split_whitespace().next().unwrap_or(tag);
+            let code = parse_number(code)
+                .ok_or_else(|| FigletFontError::InvalidCodeTag(code.to_string()))?;
+            let glyph = read_glyph(&mut lines)?;
+            if let Some(ch) = u32::try_from(code).ok().and_then(char::from_u32) {
+                glyphs.insert(ch, glyph);
+            }
+        }
+        Ok(Self {
+            height: height as u32,
+            hardblank,
+            layout,
+            glyphs,
+        })
+    }
+
+    /// A font 5 cells high, drawn with full block characters
+    pub fn block() -> Self {
+        Self::parse(include_bytes!("fonts/block.flf")).expect("invalid bundled font")
+    }
+
+    /// A font 3 cells high, drawn with half block characters
+    pub fn half_block() -> Self {
+        Self::parse(include_bytes!("fonts/half_block.flf")).expect("invalid bundled font")
+    }
+
+    /// The number of rows in a line of big text
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The layout the font asks to be drawn with
+    pub fn layout(&self) -> FigletLayout {
+        self.layout
+    }
+
+    fn glyph(&self, ch: char) -> Option<&Vec<Vec<char>>> {
+        self.glyphs.get(&ch).or_else(|| self.glyphs.get(&'\0'))
+    }
+
+    /// The number of columns by which `glyph` can be moved left over `rows`
+    fn overlap(&self, rows: &[Vec<char>], glyph: &[Vec<char>], layout: FigletLayout) -> usize {
+        let glyph_width = glyph.iter().map(Vec::len).max().unwrap_or(0);
+        // the number of spaces at the end of each row and the start of the
+        // corresponding row of the glyph
+        let gaps = rows
+            .iter()
+            .zip(glyph)
+            .map(|(row, glyph_row)| {
+                let trailing = row.iter().rev().take_while(|&&ch| ch == ' ').count();
+                let leading = glyph_row.iter().take_while(|&&ch| ch == ' ').count();
+                (trailing, leading)
+            })
+            .collect::<Vec<_>>();
+        let fit = gaps
+            .iter()
+            .map(|(trailing, leading)| trailing + leading)
+            .min()
+            .unwrap_or(0);
+        let width = rows.iter().map(Vec::len).min().unwrap_or(0);
+        let fit = fit.min(glyph_width).min(width);
+        if layout != FigletLayout::Smushing || fit >= glyph_width.min(width) {
+            return fit;
+        }
+        let smush = fit + 1;
+        // in rows whose gap is narrower than `smush`, the last character of the row
+        // lands on the first character of the glyph row
+        let smushable =
+            rows.iter()
+                .zip(glyph)
+                .zip(gaps)
+                .all(|((row, glyph_row), (trailing, leading))| {
+                    if trailing + leading >= smush {
+                        return true;
+                    }
+                    let left = row.len().checked_sub(trailing + 1).map_or(' ', |i| row[i]);
+                    let right = glyph_row.get(leading).cloned().unwrap_or(' ');
+                    left == ' '
+                        || right == ' '
+                        || (left != self.hardblank && right != self.hardblank)
+                });
+        if smushable {
+            smush
+        } else {
+            fit
+        }
+    }
+
+    fn render_line(&self, text: &str, layout: FigletLayout) -> Vec<String> {
+        let mut rows = vec![Vec::new(); self.height as usize];
+        for glyph in text.chars().filter_map(|ch| self.glyph(ch)) {
+            let overlap = match layout {
+                FigletLayout::FullWidth => 0,
+                _ => self.overlap(&rows, glyph, layout),
+            };
+            for (row, glyph_row) in rows.iter_mut().zip(glyph) {
+                let start = row.len() - overlap;
+                for (i, &ch) in glyph_row.iter().enumerate() {
+                    match row.get_mut(start + i) {
+                        Some(cell) => {
+                            if ch != ' ' {
+                                *cell = ch;
+                            }
+                        }
+                        None => row.push(ch),
+                    }
+                }
+            }
+        }
+        rows.into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|ch| if ch == self.hardblank { ' ' } else { ch })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The rows of cells of `text` in big letters. Each line of `text` is drawn
+    /// below the previous one. Characters missing from the font are skipped.
+    pub fn render(&self, text: &str, layout: FigletLayout) -> Vec<String> {
+        text.lines()
+            .flat_map(|line| self.render_line(line, layout))
+            .collect()
+    }
+}
+
+/// Draws text in big letters using a FIGlet font. Each line of big text is
+/// aligned within the context, with justified text drawn as left-aligned.
+#[derive(Debug, Clone)]
+pub struct FigletView {
+    pub style: Style,
+    pub alignment: TextAlignment,
+    pub layout: FigletLayout,
+    font: FigletFont,
+}
+
+impl FigletView {
+    pub fn new(font: FigletFont) -> Self {
+        Self {
+            style: DEFAULT_STYLE,
+            alignment: TextAlignment::Left,
+            layout: font.layout(),
+            font,
+        }
+    }
+    pub fn with_style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self { alignment, ..self }
+    }
+    pub fn with_layout(self, layout: FigletLayout) -> Self {
+        Self { layout, ..self }
+    }
+    pub fn font(&self) -> &FigletFont {
+        &self.font
+    }
+}
+
+impl<S: AsRef<str>> View<S> for FigletView {
+    fn view<F: Frame, C: ColModify>(&mut self, text: S, context: ViewContext<C>, frame: &mut F) {
+        let rows = self.font.render(text.as_ref(), self.layout);
+        let height = self.font.height as usize;
+        for (line_rows, line_y) in rows.chunks(height).zip((0..).step_by(height)) {
+            let width = line_rows
+                .iter()
+                .map(|row| row.chars().count())
+                .max()
+                .unwrap_or(0) as u32;
+            let spare = context.size.width().saturating_sub(width) as i32;
+            let x_offset = match self.alignment {
+                TextAlignment::Left | TextAlignment::Justify => 0,
+                TextAlignment::Centre => spare / 2,
+                TextAlignment::Right => spare,
+            };
+            for (y, row) in line_rows.iter().enumerate() {
+                for (x, ch) in row.chars().enumerate() {
+                    let coord = Coord::new(x_offset + x as i32, (line_y + y) as i32);
+                    let view_cell = ViewCell::new().with_character(ch).with_style(self.style);
+                    frame.set_cell_relative(coord, 0, view_cell, context);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FONT: &str = "flf2a$ 2 2 5 0 1\n\
+        test font\n";
+
+    fn font() -> FigletFont {
+        let mut text = FONT.to_string();
+        for code in 32..127u8 {
+            let glyph = match code {
+                b' ' => "$$@\n$$@@\n",
+                b'A' => " /\\@\n/ |@@\n",
+                b'B' => "|) @\n|) @@\n",
+                b'C' => "C$ @\nC  @@\n",
+                b'D' => "D@\n D@@\n",
+                _ => "@\n@@\n",
+            };
+            text.push_str(glyph);
+        }
+        for _ in REQUIRED_CHARACTERS {
+            text.push_str("@\n@@\n");
+        }
+        text.push_str("0x263A smiley\n:)@\n  @@\n");
+        FigletFont::parse(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let font = font();
+        assert_eq!(font.height(), 2);
+        assert_eq!(font.layout(), FigletLayout::Fitting);
+        assert_eq!(
+            font.render("AB A", FigletLayout::FullWidth),
+            &[" /\\|)    /\\", "/ ||)   / |"]
+        );
+        assert_eq!(
+            font.render("AB A", FigletLayout::Fitting),
+            &[" /\\|)   /\\", "/ ||)  / |"]
+        );
+        assert_eq!(
+            font.render("BA", FigletLayout::Fitting),
+            &["|) /\\", "|)/ |"]
+        );
+        assert_eq!(
+            font.render("BA", FigletLayout::Smushing),
+            &["|)/\\", "|/ |"]
+        );
+        assert_eq!(font.render("CD", FigletLayout::Smushing), &["C D", "C  D"]);
+        assert_eq!(
+            font.render("A\n\u{263A}", FigletLayout::Fitting),
+            &[" /\\", "/ |", ":)", "  "]
+        );
+        assert_eq!(
+            FigletFont::parse(b"flf2a$ x").unwrap_err(),
+            FigletFontError::InvalidHeader("height".to_string())
+        );
+    }
+
+    #[test]
+    fn bundled_fonts() {
+        let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(9, 3));
+        let context = ViewContext::default_with_size(Size::new(9, 3));
+        FigletView::new(FigletFont::half_block())
+            .with_alignment(TextAlignment::Centre)
+            .view("Hi", context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &[" █ █ ▀   ", " █▀█ █   ", " ▀ ▀ ▀   "]
+        );
+        assert_eq!(FigletFont::block().height(), 5);
+    }
+}
@@ -0,0 +1,544 @@
+use crate::default::*;
+use crate::wrap::Wrap;
+use crate::{RichTextPart, TextLayout};
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Text,
+    Keyword,
+    /// Values with names, such as `true` and `null`
+    Literal,
+    Number,
+    String,
+    Comment,
+    Type,
+    Function,
+    /// Keys of objects and tables
+    Key,
+    /// Table headers, such as `[dependencies]`
+    Section,
+    Punctuation,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub range: Range<usize>,
+    pub kind: TokenKind,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct HighlightTheme {
+    pub text: Style,
+    pub keyword: Style,
+    pub literal: Style,
+    pub number: Style,
+    pub string: Style,
+    pub comment: Style,
+    pub type_: Style,
+    pub function: Style,
+    pub key: Style,
+    pub section: Style,
+    pub punctuation: Style,
+    pub line_number: Style,
+}
+
+impl HighlightTheme {
+    pub fn style(&self, kind: TokenKind) -> Style {
+        match kind {
+            TokenKind::Text => self.text,
+            TokenKind::Keyword => self.keyword,
+            TokenKind::Literal => self.literal,
+            TokenKind::Number => self.number,
+            TokenKind::String => self.string,
+            TokenKind::Comment => self.comment,
+            TokenKind::Type => self.type_,
+            TokenKind::Function => self.function,
+            TokenKind::Key => self.key,
+            TokenKind::Section => self.section,
+            TokenKind::Punctuation => self.punctuation,
+        }
+    }
+}
+
+impl Default for HighlightTheme {
+    fn default() -> Self {
+        let text = DEFAULT_STYLE;
+        Self {
+            text,
+            keyword: Style::new()
+                .with_foreground(Rgb24::new(255, 127, 187))
+                .with_bold(true),
+            literal: Style::new().with_foreground(Rgb24::new(187, 127, 255)),
+            number: Style::new().with_foreground(Rgb24::new(187, 127, 255)),
+            string: Style::new().with_foreground(Rgb24::new(187, 255, 127)),
+            comment: Style::new().with_foreground(Rgb24::new_grey(127)),
+            type_: Style::new().with_foreground(Rgb24::new(63, 187, 255)),
+            function: Style::new().with_foreground(Rgb24::new(255, 187, 63)),
+            key: Style::new().with_foreground(Rgb24::new(63, 187, 255)),
+            section: Style::new()
+                .with_foreground(Rgb24::new(255, 187, 63))
+                .with_bold(true),
+            punctuation: Style::new().with_foreground(Rgb24::new_grey(187)),
+            line_number: Style::new().with_foreground(Rgb24::new_grey(127)),
+        }
+    }
+}
+
+/// Splits source text into tokens for colouring
+pub trait Highlighter {
+    /// Returns tokens covering `text` in order. Text between tokens is drawn with
+    /// the style of `TokenKind::Text`.
+    fn tokens(&self, text: &str) -> Vec<Token>;
+
+    fn highlight<'a>(&self, text: &'a str, theme: &HighlightTheme) -> Vec<RichTextPart<'a>> {
+        let mut parts = Vec::new();
+        let mut index = 0;
+        for token in self.tokens(text) {
+            if token.range.start > index {
+                parts.push(RichTextPart::new(
+                    &text[index..token.range.start],
+                    theme.text,
+                ));
+            }
+            parts.push(RichTextPart::new(
+                &text[token.range.clone()],
+                theme.style(token.kind),
+            ));
+            index = token.range.end;
+        }
+        if index < text.len() {
+            parts.push(RichTextPart::new(&text[index..], theme.text));
+        }
+        parts
+    }
+}
+
+/// How keys are recognised by a `Syntax`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keys {
+    None,
+    /// Strings followed by a `:`, as in JSON
+    QuotedBeforeColon,
+    /// Bare words or strings followed by a `=` or `.`, as in TOML
+    BeforeEquals,
+}
+
+/// A highlighter made of simple tokenization rules, which recognises comments,
+/// strings, numbers and words, but doesn't parse the text. Rules for some common
+/// languages are provided as constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syntax {
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// Whether a `'` may start a character literal or a lifetime, as in Rust
+    char_literals: bool,
+    keywords: &'static [&'static str],
+    literals: &'static [&'static str],
+    /// Whether words starting with a capital letter are types
+    capitalised_types: bool,
+    /// Whether words followed by a `(` or `!` are functions
+    functions: bool,
+    keys: Keys,
+    /// Whether `[` at the start of a line begins a section header
+    sections: bool,
+    /// Whether `-` may appear in words
+    dashed_words: bool,
+}
+
+impl Syntax {
+    pub const RUST: Self = Self {
+        line_comment: Some("//"),
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+        char_literals: true,
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+            "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+            "type", "unsafe", "use", "where", "while",
+        ],
+        literals: &["true", "false"],
+        capitalised_types: true,
+        functions: true,
+        keys: Keys::None,
+        sections: false,
+        dashed_words: false,
+    };
+
+    pub const JSON: Self = Self {
+        line_comment: None,
+        block_comment: None,
+        quotes: &['"'],
+        char_literals: false,
+        keywords: &[],
+        literals: &["true", "false", "null"],
+        capitalised_types: false,
+        functions: false,
+        keys: Keys::QuotedBeforeColon,
+        sections: false,
+        dashed_words: false,
+    };
+
+    pub const TOML: Self = Self {
+        line_comment: Some("#"),
+        block_comment: None,
+        quotes: &['"', '\''],
+        char_literals: false,
+        keywords: &[],
+        literals: &["true", "false", "inf", "nan"],
+        capitalised_types: false,
+        functions: false,
+        keys: Keys::BeforeEquals,
+        sections: true,
+        dashed_words: true,
+    };
+
+    /// The syntax for files with the extension `extension`, if one is provided
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "rs" => Some(Self::RUST),
+            "json" => Some(Self::JSON),
+            "toml" => Some(Self::TOML),
+            _ => None,
+        }
+    }
+
+    fn is_word_character(&self, ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_' || (self.dashed_words && ch == '-')
+    }
+
+    /// The kind of a string or word ending at `end`, judging by what follows it
+    fn key_or(&self, text: &str, end: usize, quoted: bool, kind: TokenKind) -> TokenKind {
+        let next = text[end..].trim_start_matches([' ', '\t']);
+        match self.keys {
+            Keys::QuotedBeforeColon if quoted && next.starts_with(':') => TokenKind::Key,
+            Keys::BeforeEquals
+                if (next.starts_with('=') && !next.starts_with("==")) || next.starts_with('.') =>
+            {
+                TokenKind::Key
+            }
+            _ => kind,
+        }
+    }
+
+    /// The end of a quoted string starting at `start`
+    fn string_end(text: &str, start: usize, quote: char) -> usize {
+        let triple = [quote; 3].iter().collect::<String>();
+        if text[start..].starts_with(triple.as_str()) {
+            let body = start + triple.len();
+            return text[body..]
+                .find(triple.as_str())
+                .map_or(text.len(), |i| body + i + triple.len());
+        }
+        let mut escaped = false;
+        for (i, ch) in text[start..].char_indices().skip(1) {
+            match ch {
+                '\n' => return start + i,
+                '\\' if quote == '"' => escaped = !escaped,
+                ch if ch == quote && !escaped => return start + i + ch.len_utf8(),
+                _ => escaped = false,
+            }
+        }
+        text.len()
+    }
+
+    /// The end of a character literal starting at `start`, if there is one
+    fn char_literal_end(text: &str, start: usize) -> Option<usize> {
+        let mut chars = text[start..].char_indices().skip(1);
+        match chars.next()? {
+            (_, '\\') => {
+                let end = text[start + 2..].find(['\'', '\n'])? + start + 2;
+                (text.as_bytes()[end] == b'\'').then_some(end + 1)
+            }
+            (_, '\n') | (_, '\'') => None,
+            _ => match chars.next()? {
+                (i, '\'') => Some(start + i + 1),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl Highlighter for Syntax {
+    fn tokens(&self, text: &str) -> Vec<Token> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut push = |range: Range<usize>, kind: TokenKind| match tokens.last_mut() {
+            Some(last) if last.kind == kind && last.range.end == range.start => {
+                last.range.end = range.end
+            }
+            _ => tokens.push(Token { range, kind }),
+        };
+        let mut line_start = true;
+        let mut i = 0;
+        while let Some(ch) = text[i..].chars().next() {
+            let rest = &text[i..];
+            let next = i + ch.len_utf8();
+            if ch.is_whitespace() {
+                line_start = line_start || ch == '\n';
+                i = next;
+                continue;
+            }
+            let at_line_start = std::mem::replace(&mut line_start, false);
+            let (end, kind) = if self
+                .line_comment
+                .is_some_and(|prefix| rest.starts_with(prefix))
+            {
+                let end = rest.find('\n').map_or(text.len(), |end| i + end);
+                (end, TokenKind::Comment)
+            } else if let Some((open, close)) = self
+                .block_comment
+                .filter(|&(open, _)| rest.starts_with(open))
+            {
+                let body = i + open.len();
+                let end = text[body..]
+                    .find(close)
+                    .map_or(text.len(), |end| body + end + close.len());
+                (end, TokenKind::Comment)
+            } else if self.sections && at_line_start && ch == '[' {
+                let line_end = rest.find('\n').map_or(text.len(), |end| i + end);
+                let end = text[i..line_end]
+                    .rfind(']')
+                    .map_or(line_end, |end| i + end + 1);
+                (end, TokenKind::Section)
+            } else if self.quotes.contains(&ch) {
+                let end = Self::string_end(text, i, ch);
+                (end, self.key_or(text, end, true, TokenKind::String))
+            } else if self.char_literals && ch == '\'' {
+                match Self::char_literal_end(text, i) {
+                    Some(end) => (end, TokenKind::String),
+                    // a lifetime
+                    None => {
+                        let end = text[next..]
+                            .find(|ch| !self.is_word_character(ch))
+                            .map_or(text.len(), |end| next + end);
+                        (end, TokenKind::Type)
+                    }
+                }
+            } else if ch.is_ascii_digit() {
+                let mut end = next;
+                for (j, ch) in text[next..].char_indices() {
+                    let digit_after_point = || {
+                        text[next + j + ch.len_utf8()..]
+                            .chars()
+                            .next()
+                            .is_some_and(|ch| ch.is_ascii_digit())
+                    };
+                    if ch.is_alphanumeric() || ch == '_' || (ch == '.' && digit_after_point()) {
+                        end = next + j + ch.len_utf8();
+                    } else {
+                        break;
+                    }
+                }
+                (end, TokenKind::Number)
+            } else if self.is_word_character(ch) {
+                let end = text[next..]
+                    .find(|ch| !self.is_word_character(ch))
+                    .map_or(text.len(), |end| next + end);
+                let word = &text[i..end];
+                let kind = if self.keywords.contains(&word) {
+                    TokenKind::Keyword
+                } else if self.literals.contains(&word) {
+                    TokenKind::Literal
+                } else if self.functions && text[end..].starts_with(['(', '!']) {
+                    TokenKind::Function
+                } else if self.capitalised_types && ch.is_uppercase() {
+                    TokenKind::Type
+                } else {
+                    TokenKind::Text
+                };
+                (end, self.key_or(text, end, false, kind))
+            } else {
+                (next, TokenKind::Punctuation)
+            };
+            push(i..end, kind);
+            i = end;
+        }
+        tokens
+    }
+}
+
+/// Draws source text coloured by a highlighter, with optional line numbers in a
+/// gutter on the left. Lines that are wrapped are only numbered on their first row.
+pub struct HighlightedTextView<H: Highlighter, W: Wrap> {
+    pub theme: HighlightTheme,
+    highlighter: H,
+    wrap: W,
+    line_numbers: bool,
+}
+
+impl<H: Highlighter, W: Wrap + Clone> HighlightedTextView<H, W> {
+    pub fn new(highlighter: H, wrap: W) -> Self {
+        Self {
+            theme: HighlightTheme::default(),
+            highlighter,
+            wrap,
+            line_numbers: false,
+        }
+    }
+    pub fn with_theme(self, theme: HighlightTheme) -> Self {
+        Self { theme, ..self }
+    }
+    pub fn with_line_numbers(self, line_numbers: bool) -> Self {
+        Self {
+            line_numbers,
+            ..self
+        }
+    }
+}
+
+impl<H, W, S> View<S> for HighlightedTextView<H, W>
+where
+    H: Highlighter,
+    W: Wrap + Clone,
+    S: AsRef<str>,
+{
+    fn view<F: Frame, C: ColModify>(&mut self, text: S, context: ViewContext<C>, frame: &mut F) {
+        let text = text.as_ref();
+        let context = if self.line_numbers {
+            let line_starts = std::iter::once(0)
+                .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+                .collect::<Vec<_>>();
+            let digits = line_starts.len().to_string().len() as u32;
+            let gutter_width = digits + 1;
+            let text_width = context.size.width().saturating_sub(gutter_width);
+            let layout = TextLayout::new(text, self.wrap.clone(), text_width);
+            for (line, &start) in line_starts.iter().enumerate() {
+                let y = layout.position(start).y;
+                let number = format!("{:>width$}", line + 1, width = digits as usize);
+                for (x, ch) in number.chars().enumerate() {
+                    let view_cell = ViewCell::new()
+                        .with_character(ch)
+                        .with_style(self.theme.line_number);
+                    frame.set_cell_relative(Coord::new(x as i32, y), 0, view_cell, context);
+                }
+            }
+            context.add_offset(Coord::new(gutter_width as i32, 0))
+        } else {
+            context
+        };
+        self.wrap.clear();
+        let mut offset = 0;
+        for part in self.highlighter.highlight(text, &self.theme) {
+            for (i, character) in part.text.char_indices() {
+                self.wrap
+                    .process_character(character, offset + i, part.style, context, frame);
+            }
+            offset += part.text.len();
+        }
+        self.wrap.flush(context, frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wrap;
+
+    fn kinds(syntax: Syntax, text: &str) -> Vec<(&str, TokenKind)> {
+        syntax
+            .tokens(text)
+            .into_iter()
+            .map(|token| (&text[token.range], token.kind))
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_languages() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds(Syntax::RUST, "fn f<'a>(c: char) -> Vec<u8> { 'x' } // done"),
+            &[
+                ("fn", Keyword),
+                ("f", Text),
+                ("<", Punctuation),
+                ("'a", Type),
+                (">(", Punctuation),
+                ("c", Text),
+                (":", Punctuation),
+                ("char", Text),
+                (")", Punctuation),
+                ("->", Punctuation),
+                ("Vec", Type),
+                ("<", Punctuation),
+                ("u8", Text),
+                (">", Punctuation),
+                ("{", Punctuation),
+                ("'x'", String),
+                ("}", Punctuation),
+                ("// done", Comment),
+            ]
+        );
+        assert_eq!(
+            kinds(Syntax::JSON, r#"{"a": "b\"", "c": [1.5, null]}"#),
+            &[
+                ("{", Punctuation),
+                ("\"a\"", Key),
+                (":", Punctuation),
+                (r#""b\"""#, String),
+                (",", Punctuation),
+                ("\"c\"", Key),
+                (":", Punctuation),
+                ("[", Punctuation),
+                ("1.5", Number),
+                (",", Punctuation),
+                ("null", Literal),
+                ("]}", Punctuation),
+            ]
+        );
+        assert_eq!(
+            kinds(Syntax::TOML, "[package]\nname-x = 'y' # z\n"),
+            &[
+                ("[package]", Section),
+                ("name-x", Key),
+                ("=", Punctuation),
+                ("'y'", String),
+                ("# z", Comment),
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_followed_by_non_ascii() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds(Syntax::RUST, "let x = 1é;"),
+            &[
+                ("let", Keyword),
+                ("x", Text),
+                ("=", Punctuation),
+                ("1é", Number),
+                (";", Punctuation),
+            ]
+        );
+        assert_eq!(
+            kinds(Syntax::TOML, "a = 5€"),
+            &[
+                ("a", Key),
+                ("=", Punctuation),
+                ("5", Number),
+                ("€", Punctuation)
+            ]
+        );
+    }
+
+    #[test]
+    fn line_numbers() {
+        let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(8, 4));
+        let context = ViewContext::default_with_size(Size::new(8, 4));
+        let mut view =
+            HighlightedTextView::new(Syntax::TOML, wrap::Word::new()).with_line_numbers(true);
+        view.view("a = 1\nlong = \"xx yy\"", context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["1 a = 1 ", "2 long  ", "  = \"xx ", "  yy\"   "]
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use crate::wrap::{Wrap, WrapSink};
+use crate::RichTextPart;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+/// A cell drawn by a wrap strategy, and the byte index of the character drawn in
+/// it, which is `None` for characters the wrap strategy added itself
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLayoutCell {
+    pub coord: Coord,
+    pub index: Option<usize>,
+    pub character: char,
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLayoutLine {
+    /// The bytes of the text on the line, including any newline or space at which
+    /// the line was broken. The ranges of consecutive lines are contiguous.
+    pub range: Range<usize>,
+    /// The number of cells from the start of the line to the end of its last drawn cell
+    pub width: u32,
+}
+
+impl TextLayoutCell {
+    fn end(&self) -> Coord {
+        self.coord + Coord::new(self.character.width().unwrap_or(0) as i32, 0)
+    }
+}
+
+/// Records the cells drawn by a wrap strategy
+#[derive(Default)]
+pub(crate) struct LayoutSink {
+    pub(crate) cells: Vec<TextLayoutCell>,
+}
+
+impl WrapSink for LayoutSink {
+    fn set_cell<C: ColModify>(
+        &mut self,
+        coord: Coord,
+        index: Option<usize>,
+        view_cell: ViewCell,
+        _context: ViewContext<C>,
+    ) {
+        if let Some(character) = view_cell.character {
+            self.cells.push(TextLayoutCell {
+                coord,
+                index,
+                character,
+            });
+        }
+    }
+}
+
+/// Where each character of some text is drawn when it's laid out by a wrap
+/// strategy at a given width, found by running the same wrap strategy that text
+/// views use without drawing anything. Byte indices refer to the text, or to the
+/// concatenated text of rich text parts. Layouts can be kept and reused for as
+/// long as the text and width stay the same.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLayout {
+    width: u32,
+    lines: Vec<TextLayoutLine>,
+    cells: Vec<TextLayoutCell>,
+    /// The position of a cursor at each character boundary of the text, in order
+    positions: Vec<(usize, Coord)>,
+}
+
+impl TextLayout {
+    pub fn new<W: Wrap>(text: &str, wrap: W, width: u32) -> Self {
+        Self::from_characters(text.char_indices(), wrap, width)
+    }
+
+    pub fn from_rich_text<'a, I, W>(parts: I, wrap: W, width: u32) -> Self
+    where
+        I: IntoIterator<Item = RichTextPart<'a>>,
+        W: Wrap,
+    {
+        let mut offset = 0;
+        let characters = parts.into_iter().flat_map(|part| {
+            let part_offset = offset;
+            offset += part.text.len();
+            part.text
+                .char_indices()
+                .map(move |(i, ch)| (part_offset + i, ch))
+        });
+        Self::from_characters(characters, wrap, width)
+    }
+
+    fn from_characters<I, W>(characters: I, mut wrap: W, width: u32) -> Self
+    where
+        I: IntoIterator<Item = (usize, char)>,
+        W: Wrap,
+    {
+        let characters = characters.into_iter().collect::<Vec<_>>();
+        let context = ViewContext::default_with_size(Size::new(width, Size::max_field()));
+        let mut sink = LayoutSink::default();
+        wrap.clear();
+        for &(i, ch) in characters.iter() {
+            wrap.process_character(ch, i, Style::new(), context, &mut sink);
+        }
+        wrap.flush(context, &mut sink);
+        Self::from_cells(characters, sink.cells, wrap.num_lines(), width)
+    }
+
+    /// Makes a layout from the cells a wrap strategy drew for `characters`, and the
+    /// number of lines it reported
+    pub(crate) fn from_cells<I>(
+        characters: I,
+        cells: Vec<TextLayoutCell>,
+        wrap_num_lines: usize,
+        width: u32,
+    ) -> Self
+    where
+        I: IntoIterator<Item = (usize, char)>,
+    {
+        let characters = characters.into_iter().collect::<Vec<_>>();
+        let text_len = characters.last().map_or(0, |&(i, ch)| i + ch.len_utf8());
+        // the first cell of each character, and the coordinate after its last cell
+        let mut extents: HashMap<usize, (Coord, Coord)> = HashMap::new();
+        for cell in cells.iter() {
+            if let Some(index) = cell.index {
+                extents
+                    .entry(index)
+                    .and_modify(|(_, end)| *end = cell.end())
+                    .or_insert((cell.coord, cell.end()));
+            }
+        }
+        let mut positions = Vec::with_capacity(characters.len() + 1);
+        let mut next_position = Coord::new(0, 0);
+        let boundaries = characters
+            .iter()
+            .map(|&(i, ch)| (i, Some(ch)))
+            .chain(std::iter::once((text_len, None)));
+        for (i, ch) in boundaries {
+            let extent = extents.get(&i);
+            let position = extent.map_or(next_position, |&(start, _)| start);
+            positions.push((i, position));
+            next_position = match (ch, extent) {
+                (Some('\n'), _) => Coord::new(0, position.y + 1),
+                (_, Some(&(_, end))) => end,
+                _ => position,
+            };
+        }
+        let num_lines = cells
+            .iter()
+            .map(|cell| cell.coord.y.max(0) as usize + 1)
+            .max()
+            .unwrap_or(0)
+            .max(wrap_num_lines)
+            .max(1);
+        // each line starts at the first character boundary on or below it
+        let mut line_starts = vec![text_len; num_lines + 1];
+        line_starts[0] = 0;
+        let mut y = 0;
+        for &(i, position) in positions.iter() {
+            let position_y = (position.y.max(0) as usize).min(num_lines - 1);
+            while y < position_y {
+                y += 1;
+                line_starts[y] = i;
+            }
+        }
+        let mut lines = line_starts
+            .windows(2)
+            .map(|range| TextLayoutLine {
+                range: range[0]..range[1],
+                width: 0,
+            })
+            .collect::<Vec<_>>();
+        for cell in cells.iter() {
+            if let Some(line) = lines.get_mut(cell.coord.y.max(0) as usize) {
+                line.width = line.width.max(cell.end().x.max(0) as u32);
+            }
+        }
+        Self {
+            width,
+            lines,
+            cells,
+            positions,
+        }
+    }
+
+    /// The width the text was laid out at
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The number of lines the text takes up, which is at least 1
+    pub fn num_lines(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn lines(&self) -> &[TextLayoutLine] {
+        &self.lines
+    }
+
+    /// The cells drawn, in the order the wrap strategy drew them
+    pub fn cells(&self) -> &[TextLayoutCell] {
+        &self.cells
+    }
+
+    /// The size of the area the text takes up
+    pub fn size(&self) -> Size {
+        let width = self.lines.iter().map(|line| line.width).max().unwrap_or(0);
+        Size::new(width, self.lines.len() as u32)
+    }
+
+    /// Where a cursor before the character at byte `index` goes. This is the first
+    /// cell of the character if it was drawn, or else the cell after the previous
+    /// character, which is past the end of the line if the line is full.
+    pub fn position(&self, index: usize) -> Coord {
+        match self
+            .positions
+            .binary_search_by_key(&index, |&(index, _)| index)
+        {
+            Ok(i) => self.positions[i].1,
+            Err(i) => self.positions[i.saturating_sub(1)].1,
+        }
+    }
+
+    /// The byte index of the character boundary closest to the left of `coord`.
+    /// Coordinates above the text map to its start, and below it to its end.
+    pub fn index_at(&self, coord: Coord) -> usize {
+        if coord.y < 0 {
+            return 0;
+        }
+        let mut line = self
+            .positions
+            .iter()
+            .filter(|(_, position)| position.y == coord.y)
+            .peekable();
+        let first = match line.peek() {
+            Some(&&(index, _)) => index,
+            None => return self.positions.last().map_or(0, |&(index, _)| index),
+        };
+        line.take_while(|(_, position)| position.x <= coord.x)
+            .last()
+            .map_or(first, |&(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{wrap, StringView};
+    use chargrid_test_grid::TestGrid;
+
+    fn view_size<W: Wrap>(text: &str, wrap: W, width: u32) -> Size {
+        let size = Size::new(width, 10);
+        let context = ViewContext::default_with_size(size);
+        StringView::new_default_style(wrap).view_size(text, context, &mut TestGrid::new(size))
+    }
+
+    #[test]
+    fn line_ranges_and_widths() {
+        let text = "hello big world\n\nx";
+        let layout = TextLayout::new(text, wrap::Word::new(), 10);
+        let lines = layout
+            .lines()
+            .iter()
+            .map(|line| (&text[line.range.clone()], line.width))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            &[("hello big ", 10), ("world\n", 5), ("\n", 0), ("x", 1)]
+        );
+        assert_eq!(layout.size(), Size::new(10, 4));
+        assert_eq!(layout.position(16), Coord::new(0, 2));
+        assert_eq!(layout.index_at(Coord::new(3, 1)), 13);
+    }
+
+    #[test]
+    fn ellipsis_matches_view() {
+        let layout = TextLayout::new("abcdefgh", wrap::Ellipsis::new(), 4);
+        assert_eq!(layout.size(), Size::new(4, 1));
+        assert_eq!(
+            layout.size(),
+            view_size("abcdefgh", wrap::Ellipsis::new(), 4)
+        );
+        assert_eq!(
+            layout.cells()[3],
+            TextLayoutCell {
+                coord: Coord::new(3, 0),
+                index: None,
+                character: '…',
+            }
+        );
+        assert_eq!(layout.position(5), Coord::new(3, 0));
+    }
+
+    #[test]
+    fn no_wrap_matches_view() {
+        let layout = TextLayout::new("ab漢", wrap::None::new(), 3);
+        assert_eq!(layout.num_lines(), 1);
+        assert_eq!(layout.size(), Size::new(2, 1));
+        assert_eq!(layout.size(), view_size("ab漢", wrap::None::new(), 3));
+        assert_eq!(layout.position(2), Coord::new(2, 0));
+    }
+}
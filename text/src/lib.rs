@@ -1,6 +1,28 @@
+mod alignment;
+mod animation;
+mod ansi;
 mod default;
+mod figlet;
+mod highlight;
+mod layout;
+mod markdown;
+mod markup;
+mod overflow;
 mod rich_text;
+mod selection;
 mod text;
+mod typewriter;
 pub mod wrap;
+pub use alignment::*;
+pub use animation::*;
+pub use ansi::*;
+pub use figlet::*;
+pub use highlight::*;
+pub use layout::*;
+pub use markdown::*;
+pub use markup::*;
+pub use overflow::*;
 pub use rich_text::*;
+pub use selection::*;
 pub use text::*;
+pub use typewriter::*;
@@ -0,0 +1,750 @@
+use crate::default::*;
+use crate::wrap::{self, Wrap};
+use crate::{RichTextPartOwned, RichTextView};
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+pub struct MarkdownStyle {
+    pub text: Style,
+    pub heading1: Style,
+    pub heading2: Style,
+    /// Used for headings of level 3 and below
+    pub heading3: Style,
+    /// Applied on top of the surrounding style for `**strong**` text
+    pub strong: Style,
+    /// Applied on top of the surrounding style for `*emphasised*` text
+    pub emphasis: Style,
+    /// Applied on top of the surrounding style for `` `inline code` ``
+    pub code: Style,
+    pub code_block: Style,
+    /// Applied on top of the surrounding style for the text of links
+    pub link: Style,
+    pub quote: Style,
+    /// Drawn to the left of each line of a block quote
+    pub quote_prefix: char,
+    pub quote_prefix_style: Style,
+    /// Drawn before each item of a bulleted list
+    pub bullet: char,
+    /// The style of bullets and the numbers of numbered lists
+    pub list_marker: Style,
+    pub rule: char,
+    pub rule_style: Style,
+}
+
+impl Default for MarkdownStyle {
+    fn default() -> Self {
+        let text = DEFAULT_STYLE;
+        Self {
+            text,
+            heading1: text.with_bold(true).with_underline(true),
+            heading2: text.with_bold(true),
+            heading3: text.with_underline(true),
+            strong: Style::new().with_bold(true),
+            emphasis: Style::new().with_underline(true),
+            code: Style::new().with_foreground(Rgb24::new(255, 187, 63)),
+            code_block: Style::new().with_foreground(Rgb24::new(255, 187, 63)),
+            link: Style::new()
+                .with_foreground(Rgb24::new(63, 187, 255))
+                .with_underline(true),
+            quote: Style::new().with_foreground(Rgb24::new_grey(187)),
+            quote_prefix: '│',
+            quote_prefix_style: Style::new().with_foreground(Rgb24::new_grey(127)),
+            bullet: '•',
+            list_marker: text,
+            rule: '─',
+            rule_style: Style::new().with_foreground(Rgb24::new_grey(127)),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Heading {
+        level: usize,
+        text: String,
+    },
+    Paragraph(String),
+    ListItem {
+        depth: u32,
+        /// The number of a numbered list item, or `None` for a bullet
+        number: Option<String>,
+        text: String,
+    },
+    Quote(String),
+    Code(String),
+    Rule,
+    Blank,
+}
+
+impl Block {
+    /// Appends a line to a block which continues onto following lines
+    fn continue_with(&mut self, line: &str) -> bool {
+        match self {
+            Block::Paragraph(text) | Block::ListItem { text, .. } | Block::Quote(text) => {
+                text.push(' ');
+                text.push_str(line);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A parsed Markdown document. Supported syntax is a subset of CommonMark:
+/// ATX headings (`#`), paragraphs, `*emphasis*`, `**strong**`, `` `code` ``,
+/// fenced code blocks, bulleted (`-`, `*`, `+`) and numbered (`1.`) lists nested
+/// by indentation, block quotes (`>`), horizontal rules and `[links](url)`.
+/// Only the text of links is shown.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markdown {
+    blocks: Vec<Block>,
+}
+
+fn is_rule(line: &str) -> bool {
+    let mut chars = line.chars().filter(|&ch| ch != ' ');
+    match chars.next() {
+        Some(first @ '-') | Some(first @ '*') | Some(first @ '_') => {
+            let rest = chars.collect::<Vec<_>>();
+            rest.len() >= 2 && rest.iter().all(|&ch| ch == first)
+        }
+        _ => false,
+    }
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&ch| ch == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if rest.is_empty() {
+        Some((level, rest))
+    } else if let Some(text) = rest.strip_prefix(' ') {
+        Some((level, text.trim().trim_end_matches('#').trim_end()))
+    } else {
+        None
+    }
+}
+
+fn list_item(line: &str) -> Option<(Option<String>, &str)> {
+    for bullet in &["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some((None, text));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let rest = &line[digits..];
+        if rest.starts_with(". ") || rest.starts_with(") ") {
+            return Some((Some(line[..(digits + 1)].to_string()), &rest[2..]));
+        }
+    }
+    None
+}
+
+impl Markdown {
+    pub fn parse(input: &str) -> Self {
+        let mut blocks = Vec::new();
+        let mut current: Option<Block> = None;
+        let mut code: Option<String> = None;
+        for line in input.lines() {
+            let trimmed = line.trim_start();
+            if let Some(code_text) = code.as_mut() {
+                if trimmed.starts_with("```") {
+                    blocks.push(Block::Code(code.take().unwrap_or_default()));
+                } else {
+                    if !code_text.is_empty() {
+                        code_text.push('\n');
+                    }
+                    code_text.push_str(line);
+                }
+                continue;
+            }
+            let indent = line
+                .chars()
+                .take_while(|ch| ch.is_whitespace())
+                .map(|ch| if ch == '\t' { 4 } else { 1 })
+                .sum::<u32>();
+            let trimmed = trimmed.trim_end();
+            let block = if trimmed.starts_with("```") {
+                code = Some(String::new());
+                None
+            } else if trimmed.is_empty() {
+                if blocks.last().is_some_and(|block| *block != Block::Blank) || current.is_some() {
+                    Some(Block::Blank)
+                } else {
+                    None
+                }
+            } else if is_rule(trimmed) {
+                Some(Block::Rule)
+            } else if let Some((level, text)) = heading(trimmed) {
+                Some(Block::Heading {
+                    level,
+                    text: text.to_string(),
+                })
+            } else if let Some((number, text)) = list_item(trimmed) {
+                blocks.extend(current.take());
+                current = Some(Block::ListItem {
+                    depth: indent / 2,
+                    number,
+                    text: text.to_string(),
+                });
+                continue;
+            } else if let Some(text) = trimmed.strip_prefix('>') {
+                let text = text.trim_start();
+                match current.as_mut() {
+                    Some(quote @ Block::Quote(_)) => {
+                        quote.continue_with(text);
+                    }
+                    _ => {
+                        blocks.extend(current.take());
+                        current = Some(Block::Quote(text.to_string()));
+                    }
+                }
+                continue;
+            } else {
+                match current.as_mut() {
+                    Some(block) => {
+                        block.continue_with(trimmed);
+                    }
+                    None => current = Some(Block::Paragraph(trimmed.to_string())),
+                }
+                continue;
+            };
+            blocks.extend(current.take());
+            if let Some(block) = block {
+                blocks.push(block);
+            }
+        }
+        if let Some(block) = current {
+            blocks.push(block);
+        }
+        if let Some(code_text) = code {
+            blocks.push(Block::Code(code_text));
+        }
+        while blocks.last() == Some(&Block::Blank) {
+            blocks.pop();
+        }
+        Self { blocks }
+    }
+}
+
+/// A run of one or two `*` or `_` characters, which can open or close emphasis
+/// depending on the characters either side of it, following CommonMark's rules
+/// for left and right-flanking delimiter runs
+struct Delimiter {
+    character: char,
+    len: usize,
+    can_open: bool,
+    can_close: bool,
+}
+
+impl Delimiter {
+    fn at(chars: &[char], i: usize) -> Self {
+        let character = chars[i];
+        let len = if chars.get(i + 1) == Some(&character) {
+            2
+        } else {
+            1
+        };
+        let before = if i == 0 { None } else { Some(chars[i - 1]) };
+        let after = chars.get(i + len).cloned();
+        let is_space = |ch: Option<char>| ch.is_none_or(char::is_whitespace);
+        let is_punctuation = |ch: Option<char>| ch.is_some_and(|ch| ch.is_ascii_punctuation());
+        let left_flanking = !is_space(after)
+            && (!is_punctuation(after) || is_space(before) || is_punctuation(before));
+        let right_flanking = !is_space(before)
+            && (!is_punctuation(before) || is_space(after) || is_punctuation(after));
+        let (can_open, can_close) = if character == '_' {
+            // underscores within words are not emphasis
+            (
+                left_flanking && (!right_flanking || is_punctuation(before)),
+                right_flanking && (!left_flanking || is_punctuation(after)),
+            )
+        } else {
+            (left_flanking, right_flanking)
+        };
+        Self {
+            character,
+            len,
+            can_open,
+            can_close,
+        }
+    }
+
+    /// Whether a run of the same length later in the text can close this one
+    fn has_closer(&self, chars: &[char], i: usize) -> bool {
+        ((i + self.len + 1)..chars.len()).any(|j| {
+            chars[j] == self.character && chars[j - 1] != self.character && {
+                let closer = Self::at(chars, j);
+                closer.len == self.len && closer.can_close
+            }
+        })
+    }
+}
+
+/// Parses emphasis, strong text, inline code and links within a block
+fn parse_inline(text: &str, base: Style, style: &MarkdownStyle) -> Vec<RichTextPartOwned> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut strong = false;
+    let mut emphasis = false;
+    let current_style = |strong: bool, emphasis: bool| {
+        let mut current_style = base;
+        if strong {
+            current_style = style.strong.coalesce(current_style);
+        }
+        if emphasis {
+            current_style = style.emphasis.coalesce(current_style);
+        }
+        current_style
+    };
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let next = chars.get(i + 1).cloned();
+        let mut flush = |current: &mut String, part_style: Style| {
+            if !current.is_empty() {
+                parts.push(RichTextPartOwned::new(std::mem::take(current), part_style));
+            }
+        };
+        match ch {
+            '\\' if next.is_some_and(|next| next.is_ascii_punctuation()) => {
+                current.extend(next);
+                i += 2;
+                continue;
+            }
+            '`' => {
+                if let Some(length) = chars[(i + 1)..].iter().position(|&ch| ch == '`') {
+                    flush(&mut current, current_style(strong, emphasis));
+                    let code = chars[(i + 1)..(i + 1 + length)].iter().collect::<String>();
+                    parts.push(RichTextPartOwned::new(
+                        code,
+                        style.code.coalesce(current_style(strong, emphasis)),
+                    ));
+                    i += length + 2;
+                    continue;
+                }
+            }
+            '*' | '_' => {
+                let delimiter = Delimiter::at(&chars, i);
+                let open = if delimiter.len == 2 { strong } else { emphasis };
+                let toggle = if open {
+                    delimiter.can_close
+                } else {
+                    delimiter.can_open && delimiter.has_closer(&chars, i)
+                };
+                if toggle {
+                    flush(&mut current, current_style(strong, emphasis));
+                    if delimiter.len == 2 {
+                        strong = !strong;
+                    } else {
+                        emphasis = !emphasis;
+                    }
+                } else {
+                    current.extend(&chars[i..(i + delimiter.len)]);
+                }
+                i += delimiter.len;
+                continue;
+            }
+            '[' => {
+                let close = chars[(i + 1)..].iter().position(|&ch| ch == ']');
+                if let Some(close) = close.map(|close| close + i + 1) {
+                    if chars.get(close + 1) == Some(&'(') {
+                        if let Some(end) = chars[close..].iter().position(|&ch| ch == ')') {
+                            flush(&mut current, current_style(strong, emphasis));
+                            let link_text = chars[(i + 1)..close].iter().collect::<String>();
+                            parts.push(RichTextPartOwned::new(
+                                link_text,
+                                style.link.coalesce(current_style(strong, emphasis)),
+                            ));
+                            i = close + end + 1;
+                            continue;
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        current.push(ch);
+        i += 1;
+    }
+    if !current.is_empty() {
+        parts.push(RichTextPartOwned::new(
+            current,
+            current_style(strong, emphasis),
+        ));
+    }
+    parts
+}
+
+/// A context starting `offset` cells into `context`, which unlike
+/// `ViewContext::add_offset` is as tall as possible, so blocks below the bottom of
+/// `context` are still laid out and can be drawn by frames which scroll.
+fn block_context<C: ColModify>(context: ViewContext<C>, offset: Coord) -> ViewContext<C> {
+    ViewContext {
+        offset: context.offset + offset,
+        size: Size::new(
+            context.size.width().saturating_sub(offset.x as u32),
+            Size::max_field(),
+        ),
+        ..context
+    }
+}
+
+/// Passes cells drawn in block contexts on to `frame` relative to the context of
+/// the whole document, so `frame` clips them to that context rather than to the
+/// much taller block context
+struct DocumentFrame<'a, F> {
+    frame: &'a mut F,
+    offset: Coord,
+    size: Size,
+}
+
+impl<'a, F: Frame> DocumentFrame<'a, F> {
+    fn document_context<C: ColModify>(&self, context: ViewContext<C>) -> ViewContext<C> {
+        ViewContext {
+            offset: self.offset,
+            size: self.size,
+            ..context
+        }
+    }
+}
+
+impl<'a, F: Frame> Frame for DocumentFrame<'a, F> {
+    fn set_cell_relative<C: ColModify>(
+        &mut self,
+        relative_coord: Coord,
+        relative_depth: i8,
+        relative_cell: ViewCell,
+        context: ViewContext<C>,
+    ) {
+        if relative_coord.is_valid(context.size) {
+            self.frame.set_cell_relative(
+                relative_coord + context.offset - self.offset,
+                relative_depth,
+                relative_cell,
+                self.document_context(context),
+            );
+        }
+    }
+    fn set_cell_absolute(
+        &mut self,
+        absolute_coord: Coord,
+        absolute_depth: i8,
+        absolute_cell: ViewCell,
+    ) {
+        self.frame
+            .set_cell_absolute(absolute_coord, absolute_depth, absolute_cell);
+    }
+    fn blend_cell_background_relative<C: ColModify, B: Blend>(
+        &mut self,
+        relative_coord: Coord,
+        relative_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+        context: ViewContext<C>,
+    ) {
+        if relative_coord.is_valid(context.size) {
+            self.frame.blend_cell_background_relative(
+                relative_coord + context.offset - self.offset,
+                relative_depth,
+                rgb24,
+                alpha,
+                blend,
+                self.document_context(context),
+            );
+        }
+    }
+    fn blend_cell_background_absolute<B: Blend>(
+        &mut self,
+        absolute_coord: Coord,
+        absolute_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+    ) {
+        self.frame.blend_cell_background_absolute(
+            absolute_coord,
+            absolute_depth,
+            rgb24,
+            alpha,
+            blend,
+        );
+    }
+    fn blend_cell_foreground_relative<C: ColModify, B: Blend>(
+        &mut self,
+        relative_coord: Coord,
+        relative_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+        context: ViewContext<C>,
+    ) {
+        if relative_coord.is_valid(context.size) {
+            self.frame.blend_cell_foreground_relative(
+                relative_coord + context.offset - self.offset,
+                relative_depth,
+                rgb24,
+                alpha,
+                blend,
+                self.document_context(context),
+            );
+        }
+    }
+    fn blend_cell_foreground_absolute<B: Blend>(
+        &mut self,
+        absolute_coord: Coord,
+        absolute_depth: i8,
+        rgb24: Rgb24,
+        alpha: u8,
+        blend: B,
+    ) {
+        self.frame.blend_cell_foreground_absolute(
+            absolute_coord,
+            absolute_depth,
+            rgb24,
+            alpha,
+            blend,
+        );
+    }
+}
+
+/// Draws rich text, returning the number of lines it took up
+fn view_parts<W: Wrap, F: Frame, C: ColModify>(
+    parts: &[RichTextPartOwned],
+    wrap: W,
+    context: ViewContext<C>,
+    frame: &mut F,
+) -> u32 {
+    RichTextView::new(wrap)
+        .view_size(
+            parts.iter().map(RichTextPartOwned::as_rich_text_part),
+            context,
+            frame,
+        )
+        .height()
+        .max(1)
+}
+
+/// Renders a `Markdown` document, wrapping text to the width of the context
+pub struct MarkdownView {
+    pub style: MarkdownStyle,
+}
+
+impl MarkdownView {
+    pub fn new(style: MarkdownStyle) -> Self {
+        Self { style }
+    }
+}
+
+impl Default for MarkdownView {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<'a> View<&'a Markdown> for MarkdownView {
+    fn view<F: Frame, C: ColModify>(
+        &mut self,
+        markdown: &'a Markdown,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) {
+        let style = &self.style;
+        let frame = &mut DocumentFrame {
+            frame,
+            offset: context.offset,
+            size: context.size,
+        };
+        let mut y = 0;
+        for block in markdown.blocks.iter() {
+            let context = block_context(context, Coord::new(0, y));
+            let height = match block {
+                Block::Heading { level, text } => {
+                    let heading_style = match level {
+                        1 => style.heading1,
+                        2 => style.heading2,
+                        _ => style.heading3,
+                    };
+                    let parts = parse_inline(text, heading_style, style);
+                    view_parts(&parts, wrap::Word::new(), context, frame)
+                }
+                Block::Paragraph(text) => {
+                    let parts = parse_inline(text, style.text, style);
+                    view_parts(&parts, wrap::Word::new(), context, frame)
+                }
+                Block::ListItem {
+                    depth,
+                    number,
+                    text,
+                } => {
+                    let marker = match number {
+                        Some(number) => format!("{} ", number),
+                        None => format!("{} ", style.bullet),
+                    };
+                    let indent = marker.width() as u32;
+                    let mut parts = vec![RichTextPartOwned::new(marker, style.list_marker)];
+                    parts.extend(parse_inline(text, style.text, style));
+                    let context = block_context(context, Coord::new(*depth as i32 * 2, 0));
+                    view_parts(&parts, wrap::HangingIndent::new(indent), context, frame)
+                }
+                Block::Quote(text) => {
+                    let parts = parse_inline(text, style.quote, style);
+                    let height = view_parts(
+                        &parts,
+                        wrap::Word::new(),
+                        block_context(context, Coord::new(2, 0)),
+                        frame,
+                    );
+                    let prefix = ViewCell::new()
+                        .with_character(style.quote_prefix)
+                        .with_style(style.quote_prefix_style);
+                    for y in 0..(height as i32) {
+                        frame.set_cell_relative(Coord::new(0, y), 0, prefix, context);
+                    }
+                    height
+                }
+                Block::Code(text) => {
+                    let parts = vec![RichTextPartOwned::new(text.clone(), style.code_block)];
+                    view_parts(&parts, wrap::None::new(), context, frame)
+                        .max(text.lines().count() as u32)
+                }
+                Block::Rule => {
+                    let rule = ViewCell::new()
+                        .with_character(style.rule)
+                        .with_style(style.rule_style);
+                    for x in 0..(context.size.width() as i32) {
+                        frame.set_cell_relative(Coord::new(x, 0), 0, rule, context);
+                    }
+                    1
+                }
+                Block::Blank => 1,
+            };
+            y += height as i32;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chargrid_test_grid::TestGrid;
+
+    #[test]
+    fn render_blocks() {
+        let markdown = Markdown::parse(
+            "# Title\n\nSome *text* here\n\n- one two three\n  - nested\n1. first\n\n> quoted\n\n```\ncode\n```\n---",
+        );
+        let size = Size::new(12, 14);
+        let mut test_grid = TestGrid::new(size);
+        MarkdownView::default().view(
+            &markdown,
+            ViewContext::default_with_size(size),
+            &mut test_grid,
+        );
+        assert_eq!(
+            test_grid.string_rows(),
+            &[
+                "Title       ",
+                "            ",
+                "Some text   ",
+                "here        ",
+                "            ",
+                "• one two   ",
+                "  three     ",
+                "  • nested  ",
+                "1. first    ",
+                "            ",
+                "│ quoted    ",
+                "            ",
+                "code        ",
+                "────────────",
+            ]
+        );
+    }
+
+    fn emphasised(text: &str) -> Vec<(String, bool)> {
+        let style = MarkdownStyle::default();
+        parse_inline(text, style.text, &style)
+            .into_iter()
+            .map(|part| (part.text, part.style == style.emphasis.coalesce(style.text)))
+            .collect()
+    }
+
+    #[test]
+    fn emphasis_needs_matching_flanking_delimiters() {
+        assert_eq!(
+            emphasised("Press * to search"),
+            &[("Press * to search".to_string(), false)]
+        );
+        assert_eq!(
+            emphasised("call snake_case_name or _this_"),
+            &[
+                ("call snake_case_name or ".to_string(), false),
+                ("this".to_string(), true)
+            ]
+        );
+        assert_eq!(
+            emphasised("a *b* c* d"),
+            &[
+                ("a ".to_string(), false),
+                ("b".to_string(), true),
+                (" c* d".to_string(), false)
+            ]
+        );
+        assert_eq!(
+            emphasised("2 * 3 * 4 and *x*y"),
+            &[
+                ("2 * 3 * 4 and ".to_string(), false),
+                ("x".to_string(), true),
+                ("y".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn clip_to_context() {
+        let markdown = Markdown::parse("one\n\ntwo\n\n---");
+        let mut test_grid = TestGrid::new(Size::new(4, 4));
+        MarkdownView::default().view(
+            &markdown,
+            ViewContext::default_with_size(Size::new(4, 2)),
+            &mut test_grid,
+        );
+        assert_eq!(test_grid.string_rows(), &["one ", "    ", "    ", "    "]);
+    }
+
+    #[test]
+    fn render_in_scroll_view() {
+        use chargrid_decorator::{VerticalScrollBarStyle, VerticalScrollViewOwned};
+        let markdown = Markdown::parse("one\n\ntwo\n\nthree\n\n> four\n\n---");
+        let size = Size::new(8, 3);
+        let context = ViewContext::default_with_size(size);
+        let mut view =
+            VerticalScrollViewOwned::new(MarkdownView::default(), VerticalScrollBarStyle::new());
+        view.view(&markdown, context, &mut TestGrid::new(size));
+        view.state.scroll_to(4, view.limits);
+        let mut test_grid = TestGrid::new(size);
+        view.view(&markdown, context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["three   ", "       █", "│ four  "]
+        );
+        view.state.scroll_to_bottom(view.limits);
+        let mut test_grid = TestGrid::new(size);
+        view.view(&markdown, context, &mut test_grid);
+        assert_eq!(
+            test_grid.string_rows(),
+            &["│ four  ", "        ", "────── █"]
+        );
+    }
+}
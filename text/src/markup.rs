@@ -0,0 +1,368 @@
+use crate::RichTextPartOwned;
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+/// Named colours which can be used in markup, such as `[fg=warning]`
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default)]
+pub struct MarkupTheme {
+    colours: HashMap<String, Rgb24>,
+}
+
+impl MarkupTheme {
+    /// A theme with no named colours
+    pub fn empty() -> Self {
+        Self {
+            colours: HashMap::new(),
+        }
+    }
+    /// A theme containing the names of the 8 standard terminal colours, and "grey"
+    pub fn new() -> Self {
+        Self::empty()
+            .with_colour("black", Rgb24::new(0, 0, 0))
+            .with_colour("red", Rgb24::new(187, 0, 0))
+            .with_colour("green", Rgb24::new(0, 187, 0))
+            .with_colour("yellow", Rgb24::new(187, 187, 0))
+            .with_colour("blue", Rgb24::new(0, 0, 187))
+            .with_colour("magenta", Rgb24::new(187, 0, 187))
+            .with_colour("cyan", Rgb24::new(0, 187, 187))
+            .with_colour("white", Rgb24::new(255, 255, 255))
+            .with_colour("grey", Rgb24::new(127, 127, 127))
+    }
+    pub fn with_colour<S: Into<String>>(mut self, name: S, rgb24: Rgb24) -> Self {
+        self.insert(name, rgb24);
+        self
+    }
+    pub fn insert<S: Into<String>>(&mut self, name: S, rgb24: Rgb24) {
+        self.colours.insert(name.into(), rgb24);
+    }
+    pub fn get(&self, name: &str) -> Option<Rgb24> {
+        self.colours.get(name).cloned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkupErrorKind {
+    /// A '[' with no matching ']'
+    UnterminatedTag,
+    UnknownTag(String),
+    /// A tag which requires a value (such as `fg`) was given without one
+    MissingValue(String),
+    /// A tag which doesn't take a value (such as `b`) was given one
+    UnexpectedValue(String),
+    /// A colour which is neither a `#rrggbb` hex code nor a name in the theme
+    InvalidColour(String),
+    /// A closing tag with no corresponding opening tag
+    UnmatchedClosingTag(String),
+    /// A closing tag which doesn't match the most recently opened tag
+    MismatchedClosingTag {
+        expected: String,
+        found: String,
+    },
+    /// An opening tag which is never closed
+    UnclosedTag(String),
+    /// A backslash followed by a character other than '[', ']' or '\'
+    InvalidEscape(char),
+    /// A backslash at the end of the input
+    TrailingBackslash,
+}
+
+impl fmt::Display for MarkupErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarkupErrorKind::UnterminatedTag => write!(f, "unterminated tag"),
+            MarkupErrorKind::UnknownTag(tag) => write!(f, "unknown tag \"{}\"", tag),
+            MarkupErrorKind::MissingValue(tag) => write!(f, "tag \"{}\" requires a value", tag),
+            MarkupErrorKind::UnexpectedValue(tag) => {
+                write!(f, "tag \"{}\" does not take a value", tag)
+            }
+            MarkupErrorKind::InvalidColour(colour) => write!(f, "invalid colour \"{}\"", colour),
+            MarkupErrorKind::UnmatchedClosingTag(tag) => {
+                write!(f, "closing tag \"{}\" was never opened", tag)
+            }
+            MarkupErrorKind::MismatchedClosingTag { expected, found } => write!(
+                f,
+                "expected closing tag \"{}\" but found \"{}\"",
+                expected, found
+            ),
+            MarkupErrorKind::UnclosedTag(tag) => write!(f, "tag \"{}\" is never closed", tag),
+            MarkupErrorKind::InvalidEscape(ch) => write!(f, "invalid escape \"\\{}\"", ch),
+            MarkupErrorKind::TrailingBackslash => write!(f, "backslash at end of input"),
+        }
+    }
+}
+
+/// An error in some markup, with the position at which it was found. `offset`
+/// is in bytes from the start of the input, and `line` and `column` count from
+/// 1, with columns measured in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkupError {
+    pub kind: MarkupErrorKind,
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl MarkupError {
+    fn new(kind: MarkupErrorKind, input: &str, offset: usize) -> Self {
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        Self {
+            kind,
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl error::Error for MarkupError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tag {
+    Bold,
+    Underline,
+    Foreground,
+    Background,
+}
+
+impl Tag {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "b" | "bold" => Some(Tag::Bold),
+            "u" | "underline" => Some(Tag::Underline),
+            "fg" => Some(Tag::Foreground),
+            "bg" => Some(Tag::Background),
+            _ => None,
+        }
+    }
+}
+
+struct OpenTag<'a> {
+    tag: Tag,
+    name: &'a str,
+    style_before: Style,
+    offset: usize,
+}
+
+fn parse_colour(value: &str, theme: &MarkupTheme) -> Option<Rgb24> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return None;
+        }
+        let component = |i: usize| u8::from_str_radix(&hex[i..(i + 2)], 16).ok();
+        Some(Rgb24::new(component(0)?, component(2)?, component(4)?))
+    } else {
+        theme.get(value)
+    }
+}
+
+fn push_part(parts: &mut Vec<RichTextPartOwned>, text: &mut String, style: Style) {
+    if !text.is_empty() {
+        parts.push(RichTextPartOwned::new(std::mem::take(text), style));
+    }
+}
+
+/// Parses text containing markup tags into rich text parts, starting with `style`.
+/// The supported tags are `[b]` (bold), `[u]` (underline), `[fg=colour]` and
+/// `[bg=colour]`, where a colour is a `#rrggbb` hex code or a name from the theme.
+/// Each tag is closed with a matching tag such as `[/fg]`, and tags may be nested.
+/// Literal brackets and backslashes are written `\[`, `\]` and `\\`.
+/// ```
+/// use chargrid_text::{parse_markup, MarkupTheme};
+/// use chargrid_render::Style;
+///
+/// let parts = parse_markup("[b]Bold[/b] [fg=red]warning[/fg]", Style::new(), &MarkupTheme::new()).unwrap();
+/// assert_eq!(parts.len(), 3);
+/// ```
+pub fn parse_markup(
+    input: &str,
+    style: Style,
+    theme: &MarkupTheme,
+) -> Result<Vec<RichTextPartOwned>, MarkupError> {
+    let error = |kind, offset| MarkupError::new(kind, input, offset);
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut style = style;
+    let mut open_tags: Vec<OpenTag> = Vec::new();
+    let mut chars = input.char_indices();
+    while let Some((offset, ch)) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some((_, escaped @ '[')) | Some((_, escaped @ ']')) | Some((_, escaped @ '\\')) => {
+                    text.push(escaped)
+                }
+                Some((_, other)) => {
+                    return Err(error(MarkupErrorKind::InvalidEscape(other), offset))
+                }
+                None => return Err(error(MarkupErrorKind::TrailingBackslash, offset)),
+            },
+            '[' => {
+                let content_start = offset + 1;
+                let content_end = match input[content_start..].find(']') {
+                    Some(length) => content_start + length,
+                    None => return Err(error(MarkupErrorKind::UnterminatedTag, offset)),
+                };
+                // skip over the tag's contents and closing bracket
+                for (i, _) in chars.by_ref() {
+                    if i == content_end {
+                        break;
+                    }
+                }
+                let content = &input[content_start..content_end];
+                push_part(&mut parts, &mut text, style);
+                if let Some(name) = content.strip_prefix('/') {
+                    let tag = Tag::from_name(name).ok_or_else(|| {
+                        error(MarkupErrorKind::UnknownTag(name.to_string()), offset)
+                    })?;
+                    match open_tags.pop() {
+                        None => {
+                            return Err(error(
+                                MarkupErrorKind::UnmatchedClosingTag(name.to_string()),
+                                offset,
+                            ))
+                        }
+                        Some(open_tag) => {
+                            if open_tag.tag != tag {
+                                return Err(error(
+                                    MarkupErrorKind::MismatchedClosingTag {
+                                        expected: open_tag.name.to_string(),
+                                        found: name.to_string(),
+                                    },
+                                    offset,
+                                ));
+                            }
+                            style = open_tag.style_before;
+                        }
+                    }
+                } else {
+                    let (name, value) = match content.find('=') {
+                        Some(i) => (&content[..i], Some(&content[(i + 1)..])),
+                        None => (content, None),
+                    };
+                    let tag = Tag::from_name(name).ok_or_else(|| {
+                        error(MarkupErrorKind::UnknownTag(name.to_string()), offset)
+                    })?;
+                    let style_before = style;
+                    style = match (tag, value) {
+                        (Tag::Bold, None) => style.with_bold(true),
+                        (Tag::Underline, None) => style.with_underline(true),
+                        (Tag::Bold, Some(_)) | (Tag::Underline, Some(_)) => {
+                            return Err(error(
+                                MarkupErrorKind::UnexpectedValue(name.to_string()),
+                                offset,
+                            ))
+                        }
+                        (Tag::Foreground, None) | (Tag::Background, None) => {
+                            return Err(error(
+                                MarkupErrorKind::MissingValue(name.to_string()),
+                                offset,
+                            ))
+                        }
+                        (Tag::Foreground, Some(value)) | (Tag::Background, Some(value)) => {
+                            let rgb24 = parse_colour(value, theme).ok_or_else(|| {
+                                error(MarkupErrorKind::InvalidColour(value.to_string()), offset)
+                            })?;
+                            if tag == Tag::Foreground {
+                                style.with_foreground(rgb24)
+                            } else {
+                                style.with_background(rgb24)
+                            }
+                        }
+                    };
+                    open_tags.push(OpenTag {
+                        tag,
+                        name,
+                        style_before,
+                        offset,
+                    });
+                }
+            }
+            other => text.push(other),
+        }
+    }
+    if let Some(open_tag) = open_tags.last() {
+        return Err(error(
+            MarkupErrorKind::UnclosedTag(open_tag.name.to_string()),
+            open_tag.offset,
+        ));
+    }
+    push_part(&mut parts, &mut text, style);
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested_tags() {
+        let theme = MarkupTheme::new().with_colour("warning", Rgb24::new(255, 136, 0));
+        let parts = parse_markup(
+            "a [b]b [fg=warning]c\\[[/fg][/b][u][bg=#102030]d[/bg][/u]",
+            Style::new(),
+            &theme,
+        )
+        .unwrap();
+        let parts = parts
+            .iter()
+            .map(|part| (part.text.as_str(), part.style))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parts,
+            &[
+                ("a ", Style::new()),
+                ("b ", Style::new().with_bold(true)),
+                (
+                    "c[",
+                    Style::new()
+                        .with_bold(true)
+                        .with_foreground(Rgb24::new(255, 136, 0))
+                ),
+                (
+                    "d",
+                    Style::new()
+                        .with_underline(true)
+                        .with_background(Rgb24::new(16, 32, 48))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn error_positions() {
+        let theme = MarkupTheme::new();
+        let error = parse_markup("ok\n  [b]x[/u]", Style::new(), &theme).unwrap_err();
+        assert_eq!(
+            error.kind,
+            MarkupErrorKind::MismatchedClosingTag {
+                expected: "b".to_string(),
+                found: "u".to_string()
+            }
+        );
+        assert_eq!((error.offset, error.line, error.column), (9, 2, 7));
+        let error = parse_markup("[fg=nope]x[/fg]", Style::new(), &theme).unwrap_err();
+        assert_eq!(
+            error.kind,
+            MarkupErrorKind::InvalidColour("nope".to_string())
+        );
+        let error = parse_markup("[b]x", Style::new(), &theme).unwrap_err();
+        assert_eq!(error.kind, MarkupErrorKind::UnclosedTag("b".to_string()));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::wrap::EllipsisPosition;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The progress of a scrolling marquee. Pass it the durations of
+/// `CommonEvent::Frame` events, and render text with `Overflow::Marquee` or
+/// `wrap::Marquee`. Text pauses at its start before each pass.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarqueeState {
+    elapsed: Duration,
+    step: Duration,
+    pause: Duration,
+    gap: u32,
+}
+
+impl MarqueeState {
+    pub const fn new() -> Self {
+        Self {
+            elapsed: Duration::from_millis(0),
+            step: Duration::from_millis(150),
+            pause: Duration::from_secs(1),
+            gap: 4,
+        }
+    }
+    /// The time taken to scroll by a single cell
+    pub const fn with_step(self, step: Duration) -> Self {
+        Self { step, ..self }
+    }
+    /// The time text spends at its start before scrolling
+    pub const fn with_pause(self, pause: Duration) -> Self {
+        Self { pause, ..self }
+    }
+    /// The number of empty cells between the end of the text and its start
+    pub const fn with_gap(self, gap: u32) -> Self {
+        Self { gap, ..self }
+    }
+    pub fn gap(&self) -> u32 {
+        self.gap
+    }
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        self.elapsed += since_last_frame;
+    }
+    /// Scroll back to the start, e.g. when the text changes
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_millis(0);
+    }
+    /// The number of cells to scroll text by, given the number of cells after
+    /// which it repeats
+    pub fn scroll(&self, period: u32) -> u32 {
+        let step = self.step.as_nanos().max(1);
+        let pause = self.pause.as_nanos();
+        let cycle = pause + step * period as u128;
+        let elapsed = self.elapsed.as_nanos() % cycle.max(1);
+        if elapsed < pause {
+            0
+        } else {
+            ((elapsed - pause) / step) as u32
+        }
+    }
+}
+
+impl Default for MarqueeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What single-line text views do with text which is wider than their context
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// Text beyond the edge of the context is not drawn
+    #[default]
+    Clip,
+    /// Text is cut short with `ellipsis` in place of the removed text
+    Ellipsis {
+        ellipsis: char,
+        position: EllipsisPosition,
+    },
+    /// Text scrolls over time
+    Marquee(MarqueeState),
+}
+
+impl Overflow {
+    /// Cut text short at the end, with a '…'
+    pub const fn ellipsis() -> Self {
+        Overflow::Ellipsis {
+            ellipsis: '…',
+            position: EllipsisPosition::End,
+        }
+    }
+    /// Advances a marquee, and has no effect on the other modes
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        if let Overflow::Marquee(state) = self {
+            state.on_frame(since_last_frame);
+        }
+    }
+}
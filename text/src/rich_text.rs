@@ -1,7 +1,10 @@
+use crate::default::*;
 use crate::wrap::{self, Wrap};
+use crate::{Overflow, TextMouseTracker};
 use chargrid_render::*;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
@@ -35,8 +38,15 @@ impl<'a> RichTextPart<'a> {
     }
 }
 
-pub struct RichTextView<W: Wrap> {
+/// Draws rich text. Byte offsets refer to the concatenated text of all parts. The
+/// view's mouse tracker records where each character was drawn, so text can be
+/// selected with a `TextSelectionState`.
+pub struct RichTextView<W: Wrap, M: ColModify = ColModifyBackground> {
+    /// Drawn through `highlight`, such as the selection of a `TextSelectionState`
+    pub selection: Option<Range<usize>>,
+    pub highlight: M,
     wrap: W,
+    mouse_tracker: TextMouseTracker,
 }
 
 impl Default for RichTextView<wrap::Word> {
@@ -47,33 +57,62 @@ impl Default for RichTextView<wrap::Word> {
 
 impl<W: Wrap> RichTextView<W> {
     pub fn new(wrap: W) -> Self {
-        Self { wrap }
+        Self {
+            selection: None,
+            highlight: DEFAULT_SELECTION_HIGHLIGHT,
+            wrap,
+            mouse_tracker: TextMouseTracker::default(),
+        }
+    }
+}
+
+impl<W: Wrap, M: ColModify> RichTextView<W, M> {
+    pub fn with_highlight<N: ColModify>(self, highlight: N) -> RichTextView<W, N> {
+        RichTextView {
+            selection: self.selection,
+            highlight,
+            wrap: self.wrap,
+            mouse_tracker: self.mouse_tracker,
+        }
+    }
+    pub fn mouse_tracker(&self) -> &TextMouseTracker {
+        &self.mouse_tracker
     }
 }
 
-impl<'a, I, W> View<I> for RichTextView<W>
+impl<'a, I, W, M> View<I> for RichTextView<W, M>
 where
     I: IntoIterator<Item = RichTextPart<'a>>,
     W: Wrap,
+    M: ColModify,
 {
     fn view<F: Frame, C: ColModify>(&mut self, parts: I, context: ViewContext<C>, frame: &mut F) {
+        let mut text =
+            self.mouse_tracker
+                .new_frame(self.selection.clone(), self.highlight, context, frame);
         self.wrap.clear();
         for part in parts {
-            for character in part.text.chars() {
-                self.wrap
-                    .process_character(character, part.style, context, frame);
-            }
+            text.process_str(&mut self.wrap, part.text, part.style, context);
         }
-        self.wrap.flush(context, frame);
+        text.flush(&mut self.wrap, context);
     }
 }
 
+/// Draws rich text on a single line. Text which is wider than the context is
+/// handled according to `overflow`, which clips it by default.
 #[derive(Default, Debug, Clone, Copy)]
-pub struct RichTextViewSingleLine;
+pub struct RichTextViewSingleLine {
+    pub overflow: Overflow,
+}
 
 impl RichTextViewSingleLine {
     pub const fn new() -> Self {
-        Self
+        Self {
+            overflow: Overflow::Clip,
+        }
+    }
+    pub const fn with_overflow(self, overflow: Overflow) -> Self {
+        Self { overflow }
     }
 }
 
@@ -82,7 +121,18 @@ where
     I: IntoIterator<Item = RichTextPart<'a>>,
 {
     fn view<F: Frame, C: ColModify>(&mut self, parts: I, context: ViewContext<C>, frame: &mut F) {
-        RichTextView::new(wrap::None::new()).view(parts, context, frame)
+        match self.overflow {
+            Overflow::Clip => RichTextView::new(wrap::None::new()).view(parts, context, frame),
+            Overflow::Ellipsis { ellipsis, position } => RichTextView::new(
+                wrap::Ellipsis::new()
+                    .with_ellipsis(ellipsis)
+                    .with_position(position),
+            )
+            .view(parts, context, frame),
+            Overflow::Marquee(state) => {
+                RichTextView::new(wrap::Marquee::new(state)).view(parts, context, frame)
+            }
+        }
     }
 }
 
@@ -108,9 +158,9 @@ where
     ) {
         self.wrap.clear();
         let part: RichTextPart = part.into();
-        for character in part.text.chars() {
+        for (i, character) in part.text.char_indices() {
             self.wrap
-                .process_character(character, part.style, context, frame);
+                .process_character(character, i, part.style, context, frame);
         }
         self.wrap.flush(context, frame);
     }
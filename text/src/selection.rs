@@ -0,0 +1,349 @@
+use crate::layout::LayoutSink;
+use crate::wrap::{Wrap, WrapSink};
+use crate::TextLayout;
+use chargrid_input::{Input, MouseButton, MouseInput};
+use chargrid_render::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::time::Duration;
+
+fn is_word_character(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Records the text last drawn by a `TextView` or `RichTextView`, and where each
+/// of its characters was drawn, so screen coordinates can be mapped back to byte
+/// offsets
+#[derive(Debug, Default)]
+pub struct TextMouseTracker {
+    last_offset: Coord,
+    last_size: Size,
+    text: String,
+    layout: Option<TextLayout>,
+}
+
+impl TextMouseTracker {
+    /// Starts recording the text drawn in `context`. Cells are drawn to `frame`,
+    /// through `highlight` if their characters are within `selection`.
+    pub(crate) fn new_frame<'a, F, M, C>(
+        &'a mut self,
+        selection: Option<Range<usize>>,
+        highlight: M,
+        context: ViewContext<C>,
+        frame: &'a mut F,
+    ) -> TrackedText<'a, F, M>
+    where
+        C: ColModify,
+    {
+        self.last_offset = context.offset;
+        self.last_size = context.size;
+        self.text.clear();
+        TrackedText {
+            tracker: self,
+            sink: LayoutSink::default(),
+            selection,
+            highlight,
+            frame,
+        }
+    }
+
+    /// The text last drawn, with parts concatenated
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The byte index of the character drawn at `coord`, or of the character
+    /// boundary nearest to it on the same line if nothing was drawn there. Returns
+    /// `None` if `coord` lies outside the area last drawn into.
+    pub fn index_from_screen_coord(&self, coord: Coord) -> Option<usize> {
+        let rel_coord = coord - self.last_offset;
+        if rel_coord.is_valid(self.last_size) {
+            Some(self.nearest_index(coord))
+        } else {
+            None
+        }
+    }
+
+    /// Like `index_from_screen_coord`, but coordinates outside the area last drawn
+    /// into are mapped to the nearest character boundary
+    pub fn nearest_index(&self, coord: Coord) -> usize {
+        let rel_coord = coord - self.last_offset;
+        self.layout
+            .as_ref()
+            .map_or(0, |layout| layout.index_at(rel_coord))
+    }
+}
+
+/// Passes text to a wrap strategy on behalf of a text view, drawing the cells it
+/// produces and recording them in the view's mouse tracker
+pub(crate) struct TrackedText<'a, F, M> {
+    tracker: &'a mut TextMouseTracker,
+    sink: LayoutSink,
+    selection: Option<Range<usize>>,
+    highlight: M,
+    frame: &'a mut F,
+}
+
+impl<'a, F: Frame, M: ColModify> TrackedText<'a, F, M> {
+    pub(crate) fn process_str<W: Wrap, C: ColModify>(
+        &mut self,
+        wrap: &mut W,
+        text: &str,
+        style: Style,
+        context: ViewContext<C>,
+    ) {
+        let offset = self.tracker.text.len();
+        self.tracker.text.push_str(text);
+        for (i, character) in text.char_indices() {
+            wrap.process_character(character, offset + i, style, context, self);
+        }
+    }
+
+    pub(crate) fn flush<W: Wrap, C: ColModify>(mut self, wrap: &mut W, context: ViewContext<C>) {
+        wrap.flush(context, &mut self);
+        let tracker = self.tracker;
+        tracker.layout = Some(TextLayout::from_cells(
+            tracker.text.char_indices(),
+            self.sink.cells,
+            wrap.num_lines(),
+            context.size.width(),
+        ));
+    }
+}
+
+impl<'a, F: Frame, M: ColModify> WrapSink for TrackedText<'a, F, M> {
+    fn set_cell<C: ColModify>(
+        &mut self,
+        coord: Coord,
+        index: Option<usize>,
+        view_cell: ViewCell,
+        context: ViewContext<C>,
+    ) {
+        self.sink.set_cell(coord, index, view_cell, context);
+        let selected = index.is_some_and(|index| {
+            self.selection
+                .as_ref()
+                .is_some_and(|selection| selection.contains(&index))
+        });
+        if selected {
+            let context = context.compose_col_modify(self.highlight);
+            self.frame.set_cell_relative(coord, 0, view_cell, context);
+        } else {
+            self.frame.set_cell_relative(coord, 0, view_cell, context);
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Granularity {
+    Character,
+    Word,
+    Line,
+}
+
+/// Text selected with the mouse in a `TextView` or `RichTextView`. Click and
+/// drag to select characters, double-click to select words, and triple-click to
+/// select lines. Pass it the durations of `CommonEvent::Frame` events so it can
+/// tell clicks in quick succession apart from separate clicks.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TextSelectionState {
+    selection: Option<Range<usize>>,
+    /// The characters, word or line first clicked, which stay selected while dragging
+    anchor: Range<usize>,
+    granularity: Granularity,
+    dragging: bool,
+    /// The number of clicks in quick succession at the same coordinate
+    clicks: u32,
+    last_press: Option<(Coord, Duration)>,
+    elapsed: Duration,
+    multi_click_time: Duration,
+}
+
+impl Default for TextSelectionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextSelectionState {
+    pub fn new() -> Self {
+        Self {
+            selection: None,
+            anchor: 0..0,
+            granularity: Granularity::Character,
+            dragging: false,
+            clicks: 0,
+            last_press: None,
+            elapsed: Duration::from_millis(0),
+            multi_click_time: Duration::from_millis(500),
+        }
+    }
+    /// The longest time between clicks for them to count as a double or triple click
+    pub fn with_multi_click_time(self, multi_click_time: Duration) -> Self {
+        Self {
+            multi_click_time,
+            ..self
+        }
+    }
+    /// The selected byte range of the text
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.selection.clone()
+    }
+    /// The selected part of the text last drawn by the view tracked by `tracker`
+    pub fn selected_text<'a>(&self, tracker: &'a TextMouseTracker) -> Option<&'a str> {
+        self.selection
+            .clone()
+            .and_then(|selection| tracker.text().get(selection))
+    }
+    pub fn clear(&mut self) {
+        self.selection = None;
+        self.dragging = false;
+    }
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+    pub fn on_frame(&mut self, since_last_frame: Duration) {
+        self.elapsed += since_last_frame;
+    }
+    /// Update the selection in response to mouse input. Returns true if the
+    /// selection changed.
+    pub fn on_input(&mut self, input: Input, tracker: &TextMouseTracker) -> bool {
+        let before = self.selection.clone();
+        match input {
+            Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            }) => match tracker.index_from_screen_coord(coord) {
+                Some(index) => {
+                    let multi_click = self.last_press.is_some_and(|(last_coord, time)| {
+                        last_coord == coord && self.elapsed - time <= self.multi_click_time
+                    });
+                    self.clicks = if multi_click { self.clicks % 3 + 1 } else { 1 };
+                    self.last_press = Some((coord, self.elapsed));
+                    self.granularity = match self.clicks {
+                        1 => Granularity::Character,
+                        2 => Granularity::Word,
+                        _ => Granularity::Line,
+                    };
+                    self.anchor = self.unit(tracker.text(), index);
+                    self.selection = match self.granularity {
+                        Granularity::Character => None,
+                        _ => Some(self.anchor.clone()),
+                    };
+                    self.dragging = true;
+                }
+                None => self.clear(),
+            },
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord,
+            }) if self.dragging => {
+                let unit = self.unit(tracker.text(), tracker.nearest_index(coord));
+                self.selection =
+                    Some(self.anchor.start.min(unit.start)..self.anchor.end.max(unit.end))
+                        .filter(|selection| !selection.is_empty());
+            }
+            Input::Mouse(MouseInput::MouseRelease { .. }) => self.dragging = false,
+            _ => (),
+        }
+        self.selection != before
+    }
+    /// The character, word or line containing `index`, depending on how the
+    /// selection was started
+    fn unit(&self, text: &str, index: usize) -> Range<usize> {
+        let index = index.min(text.len());
+        let next = text[index..]
+            .chars()
+            .next()
+            .map_or(index, |ch| index + ch.len_utf8());
+        match self.granularity {
+            Granularity::Character => index..next,
+            Granularity::Word => {
+                if !text[index..].starts_with(is_word_character) {
+                    return index..next;
+                }
+                let start = text[..index]
+                    .rfind(|ch| !is_word_character(ch))
+                    .map_or(0, |i| {
+                        i + text[i..].chars().next().map_or(0, char::len_utf8)
+                    });
+                let end = text[index..]
+                    .find(|ch| !is_word_character(ch))
+                    .map_or(text.len(), |i| index + i);
+                start..end
+            }
+            Granularity::Line => {
+                let start = text[..index].rfind('\n').map_or(0, |i| i + 1);
+                let end = text[index..].find('\n').map_or(text.len(), |i| index + i);
+                start..end
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::default::DEFAULT_SELECTION_HIGHLIGHT;
+    use crate::{wrap, TextView};
+
+    fn press(coord: Coord) -> Input {
+        Input::Mouse(MouseInput::MousePress {
+            button: MouseButton::Left,
+            coord,
+        })
+    }
+
+    #[test]
+    fn select_by_dragging_and_clicking() {
+        let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(10, 3));
+        let context = ViewContext::default_with_size(Size::new(10, 3)).add_offset(Coord::new(0, 1));
+        let mut view = TextView::new_default_style(wrap::Word::new());
+        let mut selection = TextSelectionState::new();
+        let text = ["one two", "\nthree"];
+        view.view(&text, context, &mut test_grid);
+        selection.on_input(press(Coord::new(1, 1)), view.mouse_tracker());
+        assert_eq!(selection.selection(), None);
+        selection.on_input(
+            Input::Mouse(MouseInput::MouseMove {
+                button: Some(MouseButton::Left),
+                coord: Coord::new(1, 2),
+            }),
+            view.mouse_tracker(),
+        );
+        assert_eq!(
+            selection.selected_text(view.mouse_tracker()),
+            Some("ne two\nth")
+        );
+        view.selection = selection.selection();
+        let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(10, 3));
+        view.view(&text, context, &mut test_grid);
+        let background = |coord| test_grid.view_cell(coord).and_then(|c| c.style.background);
+        assert_eq!(background(Coord::new(0, 1)), None);
+        assert_eq!(
+            background(Coord::new(1, 1)),
+            Some(DEFAULT_SELECTION_HIGHLIGHT.0)
+        );
+        assert_eq!(
+            background(Coord::new(1, 2)),
+            Some(DEFAULT_SELECTION_HIGHLIGHT.0)
+        );
+        assert_eq!(background(Coord::new(2, 2)), None);
+        selection.on_input(press(Coord::new(5, 1)), view.mouse_tracker());
+        selection.on_input(press(Coord::new(5, 1)), view.mouse_tracker());
+        assert_eq!(selection.selected_text(view.mouse_tracker()), Some("two"));
+        selection.on_input(press(Coord::new(5, 1)), view.mouse_tracker());
+        assert_eq!(
+            selection.selected_text(view.mouse_tracker()),
+            Some("one two")
+        );
+        selection.on_frame(Duration::from_secs(1));
+        selection.on_input(press(Coord::new(5, 1)), view.mouse_tracker());
+        assert_eq!(selection.selection(), None);
+        selection.on_input(press(Coord::new(0, 0)), view.mouse_tracker());
+        assert!(!selection.is_dragging());
+    }
+}
@@ -1,37 +1,67 @@
 use crate::default::*;
 use crate::wrap::{self, Wrap};
+use crate::{Overflow, TextMouseTracker};
 use chargrid_render::*;
+use std::ops::Range;
 
-pub struct TextView<W: Wrap> {
+/// Draws text made of several parts with the same style. Byte offsets refer to
+/// the concatenated text of all parts. The view's mouse tracker records where
+/// each character was drawn, so text can be selected with a `TextSelectionState`.
+pub struct TextView<W: Wrap, M: ColModify = ColModifyBackground> {
     pub style: Style,
+    /// Drawn through `highlight`, such as the selection of a `TextSelectionState`
+    pub selection: Option<Range<usize>>,
+    pub highlight: M,
     wrap: W,
+    mouse_tracker: TextMouseTracker,
 }
 
 impl<W: Wrap> TextView<W> {
     pub fn new(style: Style, wrap: W) -> Self {
-        Self { style, wrap }
+        Self {
+            style,
+            selection: None,
+            highlight: DEFAULT_SELECTION_HIGHLIGHT,
+            wrap,
+            mouse_tracker: TextMouseTracker::default(),
+        }
     }
     pub fn new_default_style(wrap: W) -> Self {
         Self::new(DEFAULT_STYLE, wrap)
     }
 }
 
-impl<S, I, W> View<I> for TextView<W>
+impl<W: Wrap, M: ColModify> TextView<W, M> {
+    pub fn with_highlight<N: ColModify>(self, highlight: N) -> TextView<W, N> {
+        TextView {
+            style: self.style,
+            selection: self.selection,
+            highlight,
+            wrap: self.wrap,
+            mouse_tracker: self.mouse_tracker,
+        }
+    }
+    pub fn mouse_tracker(&self) -> &TextMouseTracker {
+        &self.mouse_tracker
+    }
+}
+
+impl<S, I, W, M> View<I> for TextView<W, M>
 where
     S: AsRef<str>,
     I: IntoIterator<Item = S>,
     W: Wrap,
+    M: ColModify,
 {
     fn view<F: Frame, C: ColModify>(&mut self, parts: I, context: ViewContext<C>, frame: &mut F) {
+        let mut text =
+            self.mouse_tracker
+                .new_frame(self.selection.clone(), self.highlight, context, frame);
         self.wrap.clear();
         for part in parts {
-            let part = part.as_ref();
-            for character in part.chars() {
-                self.wrap
-                    .process_character(character, self.style, context, frame);
-            }
+            text.process_str(&mut self.wrap, part.as_ref(), self.style, context);
         }
-        self.wrap.flush(context, frame);
+        text.flush(&mut self.wrap, context);
     }
 }
 
@@ -57,30 +87,37 @@ where
     fn view<F: Frame, C: ColModify>(&mut self, part: S, context: ViewContext<C>, frame: &mut F) {
         self.wrap.clear();
         let part = part.as_ref();
-        for character in part.chars() {
+        for (i, character) in part.char_indices() {
             self.wrap
-                .process_character(character, self.style, context, frame);
+                .process_character(character, i, self.style, context, frame);
         }
         self.wrap.flush(context, frame);
     }
 }
 
+/// Draws text on a single line. Text which is wider than the context is handled
+/// according to `overflow`, which clips it by default.
 #[derive(Debug, Clone, Copy)]
 pub struct StringViewSingleLine {
     pub style: Style,
+    pub overflow: Overflow,
 }
 
 impl Default for StringViewSingleLine {
     fn default() -> Self {
-        Self {
-            style: DEFAULT_STYLE,
-        }
+        Self::new(DEFAULT_STYLE)
     }
 }
 
 impl StringViewSingleLine {
     pub const fn new(style: Style) -> Self {
-        Self { style }
+        Self {
+            style,
+            overflow: Overflow::Clip,
+        }
+    }
+    pub const fn with_overflow(self, overflow: Overflow) -> Self {
+        Self { overflow, ..self }
     }
 }
 
@@ -89,13 +126,29 @@ where
     S: AsRef<str>,
 {
     fn view<F: Frame, C: ColModify>(&mut self, part: S, context: ViewContext<C>, frame: &mut F) {
-        StringView::new(self.style, wrap::None::new()).view(part, context, frame);
+        match self.overflow {
+            Overflow::Clip => {
+                StringView::new(self.style, wrap::None::new()).view(part, context, frame)
+            }
+            Overflow::Ellipsis { ellipsis, position } => StringView::new(
+                self.style,
+                wrap::Ellipsis::new()
+                    .with_ellipsis(ellipsis)
+                    .with_position(position),
+            )
+            .view(part, context, frame),
+            Overflow::Marquee(state) => {
+                StringView::new(self.style, wrap::Marquee::new(state)).view(part, context, frame)
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::MarqueeState;
+    use std::time::Duration;
 
     #[test]
     fn word_wrap_very_wide_context() {
@@ -107,6 +160,23 @@ mod test {
         assert_eq!(test_grid.string_rows(), &["hello ".to_string()]);
     }
 
+    #[test]
+    fn single_line_marquee_advances() {
+        let state = MarqueeState::new()
+            .with_step(Duration::from_millis(100))
+            .with_pause(Duration::from_millis(0));
+        let mut view =
+            StringViewSingleLine::new(Style::new()).with_overflow(Overflow::Marquee(state));
+        view.overflow.on_frame(Duration::from_millis(300));
+        let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(4, 1));
+        view.view(
+            "abcdef",
+            ViewContext::default_with_size(Size::new(4, 1)),
+            &mut test_grid,
+        );
+        assert_eq!(test_grid.string_rows(), &["def ".to_string()]);
+    }
+
     #[test]
     fn word_wrap_typical_string() {
         let mut test_grid = chargrid_test_grid::TestGrid::new(Size::new(80, 10));
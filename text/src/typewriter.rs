@@ -0,0 +1,243 @@
+use crate::wrap::Wrap;
+use crate::{AnimatedTextView, RichTextPartOwned, TextAnimationState};
+use chargrid_event_routine::{
+    common_event::CommonEvent, event_or_peek_with_handled, EventOrPeek, EventRoutine, Handled,
+    ViewSelector,
+};
+use chargrid_input::{Input, MouseInput};
+use chargrid_render::*;
+use std::marker::PhantomData;
+
+/// How a `TypewriterRoutine` finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypewriterEnd {
+    /// All the text was revealed without being skipped
+    Completed,
+    /// A key was pressed or the mouse was clicked to reveal the rest of the text
+    Skipped,
+}
+
+/// An event routine which reveals text a character at a time, drawing it with an
+/// `AnimatedTextView`. Pressing a key or clicking reveals the rest of the text at
+/// once. By default the routine then waits for another key press or click before
+/// returning, so the whole text can be read.
+pub struct TypewriterRoutine<S, D, W> {
+    s: S,
+    data: PhantomData<D>,
+    wrap: PhantomData<W>,
+    parts: Vec<RichTextPartOwned>,
+    num_chars: usize,
+    state: TextAnimationState,
+    skipped: bool,
+    wait_for_input: bool,
+}
+
+impl<S, D, W> TypewriterRoutine<S, D, W>
+where
+    S: ViewSelector<ViewOutput = AnimatedTextView<W>>,
+    W: Wrap,
+{
+    pub fn new(s: S, parts: Vec<RichTextPartOwned>) -> Self {
+        let num_chars = parts.iter().map(|part| part.text.chars().count()).sum();
+        Self {
+            s,
+            data: PhantomData,
+            wrap: PhantomData,
+            parts,
+            num_chars,
+            state: TextAnimationState::new(),
+            skipped: false,
+            wait_for_input: true,
+        }
+    }
+    /// Sets the rate at which text is revealed and faded in
+    pub fn with_state(self, state: TextAnimationState) -> Self {
+        Self { state, ..self }
+    }
+    /// Whether to wait for a key press or click after all the text is revealed,
+    /// rather than returning straight away
+    pub fn with_wait_for_input(self, wait_for_input: bool) -> Self {
+        Self {
+            wait_for_input,
+            ..self
+        }
+    }
+    fn end(&self) -> TypewriterEnd {
+        if self.skipped {
+            TypewriterEnd::Skipped
+        } else {
+            TypewriterEnd::Completed
+        }
+    }
+}
+
+impl<S, D, W> EventRoutine for TypewriterRoutine<S, D, W>
+where
+    S: ViewSelector<ViewOutput = AnimatedTextView<W>>,
+    W: Wrap,
+{
+    type Return = TypewriterEnd;
+    type Data = D;
+    type View = S::ViewInput;
+    type Event = CommonEvent;
+
+    fn handle<EP>(
+        self,
+        _data: &mut Self::Data,
+        _view: &Self::View,
+        event_or_peek: EP,
+    ) -> Handled<Self::Return, Self>
+    where
+        EP: EventOrPeek<Event = Self::Event>,
+    {
+        event_or_peek_with_handled(event_or_peek, self, |mut s, event| match event {
+            CommonEvent::Frame(since_last_frame) => {
+                s.state.on_frame(since_last_frame);
+                if !s.wait_for_input && s.state.is_complete(s.num_chars) {
+                    Handled::Return(s.end())
+                } else {
+                    Handled::Continue(s)
+                }
+            }
+            CommonEvent::Input(Input::Keyboard(_))
+            | CommonEvent::Input(Input::Mouse(MouseInput::MousePress { .. })) => {
+                if s.state.is_complete(s.num_chars) {
+                    Handled::Return(s.end())
+                } else {
+                    s.state.skip();
+                    s.skipped = true;
+                    if s.wait_for_input {
+                        Handled::Continue(s)
+                    } else {
+                        Handled::Return(s.end())
+                    }
+                }
+            }
+            CommonEvent::Input(_) => Handled::Continue(s),
+        })
+    }
+
+    fn view<F, C>(
+        &self,
+        _data: &Self::Data,
+        view: &mut Self::View,
+        context: ViewContext<C>,
+        frame: &mut F,
+    ) where
+        F: Frame,
+        C: ColModify,
+    {
+        let parts = self.parts.iter().map(RichTextPartOwned::as_rich_text_part);
+        self.s
+            .view_mut(view)
+            .view((&self.state, parts), context, frame);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wrap;
+    use chargrid_event_routine::Event;
+    use chargrid_input::{keys, MouseButton};
+    use chargrid_test_grid::TestGrid;
+    use std::time::Duration;
+
+    #[derive(Clone, Copy)]
+    struct SelectAnimatedTextView;
+
+    impl ViewSelector for SelectAnimatedTextView {
+        type ViewInput = AnimatedTextView<wrap::Word>;
+        type ViewOutput = AnimatedTextView<wrap::Word>;
+        fn view<'a>(&self, input: &'a Self::ViewInput) -> &'a Self::ViewOutput {
+            input
+        }
+        fn view_mut<'a>(&self, input: &'a mut Self::ViewInput) -> &'a mut Self::ViewOutput {
+            input
+        }
+    }
+
+    type Typewriter = TypewriterRoutine<SelectAnimatedTextView, (), wrap::Word>;
+
+    fn typewriter() -> Typewriter {
+        let parts = vec![RichTextPartOwned::new("abc".to_string(), Style::new())];
+        TypewriterRoutine::new(SelectAnimatedTextView, parts)
+            .with_state(TextAnimationState::new().with_char_delay(Duration::from_millis(10)))
+    }
+
+    fn frame(millis: u64) -> CommonEvent {
+        CommonEvent::Frame(Duration::from_millis(millis))
+    }
+
+    fn click() -> CommonEvent {
+        CommonEvent::Input(Input::Mouse(MouseInput::MousePress {
+            button: MouseButton::Left,
+            coord: Coord::new(0, 0),
+        }))
+    }
+
+    /// Passes `events` to `routine`, returning what it returned or the routine if
+    /// it's still running
+    fn run(mut routine: Typewriter, events: &[CommonEvent]) -> Result<TypewriterEnd, Typewriter> {
+        let view = AnimatedTextView::new(wrap::Word::new());
+        for &event in events {
+            match routine.handle(&mut (), &view, Event::new(event)) {
+                Handled::Return(end) => return Ok(end),
+                Handled::Continue(r) => routine = r,
+            }
+        }
+        Err(routine)
+    }
+
+    fn render(routine: &Typewriter) -> Vec<String> {
+        let size = Size::new(3, 1);
+        let mut view = AnimatedTextView::new(wrap::Word::new());
+        let mut test_grid = TestGrid::new(size);
+        let context = ViewContext::default_with_size(size);
+        routine.view(&(), &mut view, context, &mut test_grid);
+        test_grid.string_rows()
+    }
+
+    #[test]
+    fn completes_without_waiting_for_input() {
+        let routine = typewriter().with_wait_for_input(false);
+        let routine = run(routine, &[frame(10)]).err().unwrap();
+        assert_eq!(render(&routine), &["ab "]);
+        assert_eq!(
+            run(routine, &[frame(10)]).ok(),
+            Some(TypewriterEnd::Completed)
+        );
+    }
+
+    #[test]
+    fn waits_for_input_after_completing() {
+        let inputs = [
+            frame(30),
+            frame(30),
+            CommonEvent::Input(Input::Keyboard(keys::RETURN)),
+        ];
+        let routine = run(typewriter(), &inputs[..2]).err().unwrap();
+        assert_eq!(render(&routine), &["abc"]);
+        assert_eq!(
+            run(routine, &inputs[2..]).ok(),
+            Some(TypewriterEnd::Completed)
+        );
+        let inputs = [frame(30), click()];
+        assert_eq!(
+            run(typewriter(), &inputs).ok(),
+            Some(TypewriterEnd::Completed)
+        );
+    }
+
+    #[test]
+    fn key_press_skips_to_the_end() {
+        let key = CommonEvent::Input(Input::Keyboard(keys::RETURN));
+        let routine = run(typewriter(), &[frame(5)]).err().unwrap();
+        assert_eq!(render(&routine), &["a  "]);
+        let routine = run(routine, &[key]).err().unwrap();
+        assert_eq!(render(&routine), &["abc"]);
+        assert_eq!(run(routine, &[key]).ok(), Some(TypewriterEnd::Skipped));
+        let routine = typewriter().with_wait_for_input(false);
+        assert_eq!(run(routine, &[click()]).ok(), Some(TypewriterEnd::Skipped));
+    }
+}
@@ -1,69 +1,523 @@
+use crate::{MarqueeState, TextAlignment};
 use chargrid_render::*;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use unicode_width::UnicodeWidthChar;
+use xi_unicode::LineBreakLeafIter;
 
-pub trait Wrap: private_wrap::Sealed {
-    #[doc(hidden)]
+/// The default distance between tab stops
+pub const DEFAULT_TAB_WIDTH: u32 = 8;
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// A strategy for laying out text. Text views call the methods of this trait
+/// in a fixed order each time they are rendered:
+///
+/// 1. `clear` is called once, before anything else, and must reset any state
+///    left over from the previous render.
+/// 2. `process_character` is called with each character of the text in order,
+///    including newlines, along with its byte index in the text and its style.
+///    The context is the same for every call. Implementations may draw each
+///    character as it arrives, or buffer characters (for example to decide where
+///    to break a line) and draw them later.
+/// 3. `flush` is called once after the last character, and must draw anything
+///    which is still buffered.
+///
+/// After `flush`, `num_lines` returns the number of lines the text took up,
+/// which is at least 1 (even for empty text) and counts lines whether or not they
+/// fit within the context.
+///
+/// Cells are drawn by passing them to a `WrapSink`, along with the index of the
+/// character drawn in them, or `None` for characters which a wrap strategy adds
+/// itself (such as an ellipsis). Text views draw straight to their frame, and
+/// `TextLayout` records where each character went.
+pub trait Wrap {
     fn clear(&mut self);
-    #[doc(hidden)]
-    fn process_character<F: Frame, C: ColModify>(
+    fn process_character<S: WrapSink, C: ColModify>(
         &mut self,
         character: char,
+        index: usize,
         style: Style,
         context: ViewContext<C>,
-        frame: &mut F,
+        sink: &mut S,
     );
-    #[doc(hidden)]
-    fn flush<F: Frame, C: ColModify>(&mut self, context: ViewContext<C>, frame: &mut F) {
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
         let _ = context;
-        let _ = frame;
+        let _ = sink;
     }
-    #[doc(hidden)]
     fn num_lines(&self) -> usize;
 }
 
+/// Receives the cells drawn by a wrap strategy. Every frame is a sink which draws
+/// the cells at depth 0 relative to the context.
+pub trait WrapSink {
+    /// `index` is the byte index of the character drawn in the cell, or `None` if
+    /// the wrap strategy added the character itself
+    fn set_cell<C: ColModify>(
+        &mut self,
+        coord: Coord,
+        index: Option<usize>,
+        view_cell: ViewCell,
+        context: ViewContext<C>,
+    );
+}
+
+impl<F: Frame> WrapSink for F {
+    fn set_cell<C: ColModify>(
+        &mut self,
+        coord: Coord,
+        _index: Option<usize>,
+        view_cell: ViewCell,
+        context: ViewContext<C>,
+    ) {
+        self.set_cell_relative(coord, 0, view_cell, context);
+    }
+}
+
+/// Spaces which must not be broken, which are drawn as regular spaces
+fn is_non_breaking_space(character: char) -> bool {
+    matches!(character, '\u{A0}' | '\u{2007}' | '\u{202F}')
+}
+
+fn is_breaking_space(character: char) -> bool {
+    character.is_whitespace() && !is_non_breaking_space(character)
+}
+
+fn display_character(character: char) -> char {
+    if is_non_breaking_space(character) {
+        ' '
+    } else {
+        character
+    }
+}
+
+/// The number of cells a tab at `position` expands to
+fn tab_stop_spaces(position: u32, tab_width: u32) -> u32 {
+    if tab_width == 0 {
+        0
+    } else {
+        tab_width - (position % tab_width)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+    /// The character is part of the previous grapheme cluster, and isn't drawn
+    Join,
+    /// The character starts a grapheme cluster which is `width` cells wide
+    Cluster {
+        width: u32,
+        break_before: bool,
+    },
+    Tab {
+        break_before: bool,
+    },
+}
+
+/// Groups characters into grapheme clusters, and finds the places where lines
+/// may be broken according to the Unicode line breaking algorithm (UAX #14).
+/// Characters without a width of their own (such as combining characters and
+/// variation selectors), and characters following a zero-width joiner, are
+/// treated as part of the preceding cluster. Since a cell can only contain a
+/// single character, only the first character of each cluster is drawn.
+#[derive(Clone, Default)]
+struct Segmenter {
+    line_break: Option<LineBreakLeafIter>,
+    after_zero_width_joiner: bool,
+}
+
+impl fmt::Debug for Segmenter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Segmenter")
+            .field("after_zero_width_joiner", &self.after_zero_width_joiner)
+            .finish()
+    }
+}
+
+impl Segmenter {
+    fn clear(&mut self) {
+        self.line_break = None;
+        self.after_zero_width_joiner = false;
+    }
+
+    /// Feeds a single character through the line breaking algorithm, returning
+    /// true if the line may be broken before it
+    fn break_before(&mut self, character: char) -> bool {
+        let mut buf = [0; 4];
+        let leaf = character.encode_utf8(&mut buf);
+        match self.line_break.as_mut() {
+            None => {
+                let mut line_break = LineBreakLeafIter::new(leaf, 0);
+                line_break.next(leaf);
+                self.line_break = Some(line_break);
+                false
+            }
+            Some(line_break) => {
+                let (index, _) = line_break.next(leaf);
+                if index == 0 {
+                    // finish the leaf so the next character starts from its beginning
+                    line_break.next(leaf);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn segment(&mut self, character: char) -> Segment {
+        let break_before = self.break_before(character);
+        let after_zero_width_joiner = self.after_zero_width_joiner;
+        self.after_zero_width_joiner = character == ZERO_WIDTH_JOINER;
+        if character == '\t' {
+            return Segment::Tab { break_before };
+        }
+        match character.width() {
+            Some(width) if width > 0 && !after_zero_width_joiner => Segment::Cluster {
+                width: width as u32,
+                break_before,
+            },
+            _ => Segment::Join,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy)]
+struct WrapCell {
+    view_cell: ViewCell,
+    index: Option<usize>,
+    width: u32,
+    is_space: bool,
+}
+
+impl WrapCell {
+    fn space(index: usize, style: Style) -> Self {
+        Self {
+            view_cell: ViewCell {
+                character: Some(' '),
+                style,
+            },
+            index: Some(index),
+            width: 1,
+            is_space: true,
+        }
+    }
+    fn character(character: char, index: usize, width: u32, style: Style) -> Self {
+        Self {
+            view_cell: ViewCell {
+                character: Some(display_character(character)),
+                style,
+            },
+            index: Some(index),
+            width,
+            is_space: is_breaking_space(character),
+        }
+    }
+}
+
+/// The line currently being laid out. Cells are buffered until the end of the
+/// line so that the whole line can be aligned.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+struct Line {
+    y: i32,
+    /// The number of cells before the start of the line
+    indent: u32,
+    width: u32,
+    cells: Vec<WrapCell>,
+}
+
+impl Line {
+    fn new() -> Self {
+        Self {
+            y: 0,
+            indent: 0,
+            width: 0,
+            cells: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.y = 0;
+        self.indent = 0;
+        self.width = 0;
+        self.cells.clear();
+    }
+
+    fn push(&mut self, wrap_cell: WrapCell) {
+        self.width += wrap_cell.width;
+        self.cells.push(wrap_cell);
+    }
+
+    /// The number of cells remaining on the line in the given context
+    fn remaining<C: ColModify>(&self, context: ViewContext<C>) -> u32 {
+        context
+            .size
+            .width()
+            .saturating_sub(self.indent)
+            .saturating_sub(self.width)
+    }
+
+    /// If the line is wider than `max_width`, removes cells from it so that it
+    /// fits with `ellipsis` in place of the removed cells
+    fn truncate(&mut self, max_width: u32, ellipsis: char, position: EllipsisPosition) {
+        if self.width <= max_width {
+            return;
+        }
+        let ellipsis_width = ellipsis.width().unwrap_or(0) as u32;
+        let budget = max_width.saturating_sub(ellipsis_width);
+        let head_budget = match position {
+            EllipsisPosition::Start => 0,
+            EllipsisPosition::Middle => budget.div_ceil(2),
+            EllipsisPosition::End => budget,
+        };
+        let mut head_width = 0;
+        let mut head = 0;
+        for cell in self.cells.iter() {
+            if head_width + cell.width > head_budget {
+                break;
+            }
+            head_width += cell.width;
+            head += 1;
+        }
+        while head > 0 && self.cells[head - 1].is_space {
+            head -= 1;
+            head_width -= self.cells[head].width;
+        }
+        let tail_budget = budget.saturating_sub(head_width);
+        let mut tail_width = 0;
+        let mut tail = self.cells.len();
+        if position != EllipsisPosition::End {
+            while tail > head && tail_width + self.cells[tail - 1].width <= tail_budget {
+                tail -= 1;
+                tail_width += self.cells[tail].width;
+            }
+            while tail < self.cells.len() && self.cells[tail].is_space {
+                tail += 1;
+            }
+        }
+        let style = if head > 0 {
+            self.cells[head - 1].view_cell.style
+        } else {
+            self.cells[tail.min(self.cells.len() - 1)].view_cell.style
+        };
+        let mut removed = self.cells.split_off(head);
+        let tail_cells = removed.split_off(tail - head);
+        self.width = head_width;
+        if ellipsis_width <= max_width {
+            self.push(WrapCell {
+                view_cell: ViewCell {
+                    character: Some(ellipsis),
+                    style,
+                },
+                index: None,
+                width: ellipsis_width,
+                is_space: false,
+            });
+        }
+        for cell in tail_cells {
+            self.push(cell);
+        }
+    }
+
+    /// Draws the buffered cells scrolled `scroll` cells to the left, followed by
+    /// `gap` empty cells and then the cells again, so that text which is wider
+    /// than the context wraps around
+    fn draw_scrolled<S: WrapSink, C: ColModify>(
+        &mut self,
+        scroll: u32,
+        gap: u32,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        let width = context.size.width() as i64;
+        let mut x = -(scroll as i64);
+        for _ in 0..2 {
+            for wrap_cell in self.cells.iter() {
+                if x >= 0 && x + wrap_cell.width as i64 <= width {
+                    sink.set_cell(
+                        Coord::new(x as i32, self.y),
+                        wrap_cell.index,
+                        wrap_cell.view_cell,
+                        context,
+                    );
+                }
+                x += wrap_cell.width as i64;
+            }
+            x += gap as i64;
+        }
+        self.cells.clear();
+        self.width = 0;
+    }
+
+    /// Draws the buffered cells. Full justification is not applied to the last
+    /// line of a paragraph.
+    fn draw<S: WrapSink, C: ColModify>(
+        &mut self,
+        alignment: TextAlignment,
+        end_of_paragraph: bool,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        let trailing_space_width: u32 = self
+            .cells
+            .iter()
+            .rev()
+            .take_while(|cell| cell.is_space)
+            .map(|cell| cell.width)
+            .sum();
+        let content_width = self.width - trailing_space_width;
+        let spare =
+            (context.size.width() as i64 - self.indent as i64 - content_width as i64).max(0);
+        let offset = match alignment {
+            TextAlignment::Left | TextAlignment::Justify => 0,
+            TextAlignment::Centre => spare / 2,
+            TextAlignment::Right => spare,
+        };
+        // the index of the first space of each gap between words
+        let gaps = if alignment == TextAlignment::Justify && !end_of_paragraph {
+            let first = self.cells.iter().position(|cell| !cell.is_space);
+            let last = self.cells.iter().rposition(|cell| !cell.is_space);
+            match (first, last) {
+                (Some(first), Some(last)) => (first..last)
+                    .filter(|&i| self.cells[i].is_space && !self.cells[i - 1].is_space)
+                    .collect::<Vec<_>>(),
+                _ => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+        let width = context.size.width() as i64;
+        let mut x = self.indent as i64 + offset;
+        for (i, wrap_cell) in self.cells.drain(..).enumerate() {
+            // a cluster which doesn't fit entirely within the context isn't drawn,
+            // so wide characters can't spill over its edge
+            if x + wrap_cell.width as i64 <= width {
+                sink.set_cell(
+                    Coord::new(x as i32, self.y),
+                    wrap_cell.index,
+                    wrap_cell.view_cell,
+                    context,
+                );
+            }
+            x += wrap_cell.width as i64;
+            if let Some(gap_index) = gaps.iter().position(|&gap| gap == i) {
+                let num_gaps = gaps.len() as i64;
+                let extra = spare / num_gaps
+                    + if (gap_index as i64) < spare % num_gaps {
+                        1
+                    } else {
+                        0
+                    };
+                x += extra;
+            }
+        }
+        self.width = 0;
+    }
+
+    fn draw_and_advance<S: WrapSink, C: ColModify>(
+        &mut self,
+        alignment: TextAlignment,
+        end_of_paragraph: bool,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        self.draw(alignment, end_of_paragraph, context, sink);
+        self.y += 1;
+    }
+}
+
+/// Doesn't break lines. Characters beyond the edge of the context are not drawn.
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct None {
-    cursor: Coord,
+    line: Line,
+    tab_width: u32,
+    alignment: TextAlignment,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    segmenter: Segmenter,
 }
 
+/// Breaks lines between words, at the opportunities given by the Unicode line
+/// breaking algorithm. Spaces at the end of a line are not drawn, and words
+/// which are too long to fit on a line by themselves are broken between
+/// characters.
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct Word {
-    cursor: Coord,
-    current_word_buffer: Vec<ViewCell>,
+    line: Line,
+    tab_width: u32,
+    alignment: TextAlignment,
+    hanging_indent: u32,
+    current_segment: Vec<WrapCell>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    segmenter: Segmenter,
 }
 
+/// Breaks lines between any two grapheme clusters
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct Char {
-    cursor: Coord,
+    line: Line,
+    tab_width: u32,
+    alignment: TextAlignment,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    segmenter: Segmenter,
 }
 
 impl None {
     pub fn new() -> Self {
         Self {
-            cursor: Coord::new(0, 0),
+            line: Line::new(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            alignment: TextAlignment::Left,
+            segmenter: Segmenter::default(),
         }
     }
+    pub fn with_tab_width(self, tab_width: u32) -> Self {
+        Self { tab_width, ..self }
+    }
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self { alignment, ..self }
+    }
 }
 
 impl Word {
     pub fn new() -> Self {
         Self {
-            cursor: Coord::new(0, 0),
-            current_word_buffer: Vec::new(),
+            line: Line::new(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            alignment: TextAlignment::Left,
+            hanging_indent: 0,
+            current_segment: Vec::new(),
+            segmenter: Segmenter::default(),
         }
     }
+    pub fn with_tab_width(self, tab_width: u32) -> Self {
+        Self { tab_width, ..self }
+    }
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self { alignment, ..self }
+    }
 }
 
 impl Char {
     pub fn new() -> Self {
         Self {
-            cursor: Coord::new(0, 0),
+            line: Line::new(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            alignment: TextAlignment::Left,
+            segmenter: Segmenter::default(),
         }
     }
+    pub fn with_tab_width(self, tab_width: u32) -> Self {
+        Self { tab_width, ..self }
+    }
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self { alignment, ..self }
+    }
 }
 
 impl Default for None {
@@ -86,164 +540,628 @@ impl Default for Char {
 
 impl Wrap for None {
     fn clear(&mut self) {
-        self.cursor = Coord::new(0, 0);
+        self.line.clear();
+        self.segmenter.clear();
     }
-    fn process_character<F: Frame, C: ColModify>(
+    fn process_character<S: WrapSink, C: ColModify>(
         &mut self,
         character: char,
+        index: usize,
         style: Style,
         context: ViewContext<C>,
-        frame: &mut F,
+        sink: &mut S,
     ) {
         match character {
             '\n' => {
-                self.cursor.x = 0;
-                self.cursor.y += 1;
+                self.line
+                    .draw_and_advance(self.alignment, true, context, sink);
+                self.segmenter.clear();
             }
-            '\r' => self.cursor.x = 0,
-            other => {
-                let view_cell = ViewCell {
-                    character: Some(other),
-                    style,
-                };
-                frame.set_cell_relative(self.cursor, 0, view_cell, context);
-                self.cursor += Coord::new(1, 0);
+            '\r' => {
+                self.line.draw(self.alignment, true, context, sink);
+                self.segmenter.clear();
             }
+            other => match self.segmenter.segment(other) {
+                Segment::Join => (),
+                Segment::Tab { .. } => {
+                    for _ in 0..tab_stop_spaces(self.line.width, self.tab_width) {
+                        self.line.push(WrapCell::space(index, style));
+                    }
+                }
+                Segment::Cluster { width, .. } => {
+                    self.line
+                        .push(WrapCell::character(other, index, width, style));
+                }
+            },
         }
     }
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.line.draw(self.alignment, true, context, sink);
+    }
     fn num_lines(&self) -> usize {
-        self.cursor.y as usize + 1
+        self.line.y as usize + 1
+    }
+}
+
+impl Word {
+    fn current_segment_width(&self) -> u32 {
+        self.current_segment.iter().map(|cell| cell.width).sum()
+    }
+
+    /// The width of the current segment, not including any spaces at its end
+    fn current_segment_word_width(&self) -> u32 {
+        let trailing_space_width: u32 = self
+            .current_segment
+            .iter()
+            .rev()
+            .take_while(|cell| cell.is_space)
+            .map(|cell| cell.width)
+            .sum();
+        self.current_segment_width() - trailing_space_width
+    }
+
+    fn push<S: WrapSink, C: ColModify>(
+        &mut self,
+        wrap_cell: WrapCell,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        if !wrap_cell.is_space
+            && self.current_segment_width() + wrap_cell.width > context.size.width()
+        {
+            // the segment is too wide to fit on a line by itself
+            self.flush_segment(context, sink);
+        }
+        self.current_segment.push(wrap_cell);
+    }
+
+    fn soft_line_break<S: WrapSink, C: ColModify>(
+        &mut self,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        self.line
+            .draw_and_advance(self.alignment, false, context, sink);
+        self.line.indent = self.hanging_indent;
+    }
+
+    /// Moves the current segment onto the current line, starting a new line
+    /// first if it doesn't fit
+    fn flush_segment<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        if context.size.width() == 0 {
+            self.current_segment.clear();
+            return;
+        }
+        // a word is only allowed to reach the last column if it starts a line
+        if self.line.width != 0 && self.current_segment_word_width() >= self.line.remaining(context)
+        {
+            self.soft_line_break(context, sink);
+        }
+        let mut current_segment = std::mem::take(&mut self.current_segment);
+        for wrap_cell in current_segment.drain(..) {
+            if wrap_cell.width > self.line.remaining(context) {
+                if wrap_cell.is_space {
+                    // spaces which don't fit at the end of a line are not drawn
+                    continue;
+                }
+                if self.line.width != 0 {
+                    self.soft_line_break(context, sink);
+                }
+            }
+            self.line.push(wrap_cell);
+        }
+        self.current_segment = current_segment;
     }
 }
 
 impl Wrap for Word {
     fn clear(&mut self) {
-        self.cursor = Coord::new(0, 0);
-        self.current_word_buffer.clear();
+        self.line.clear();
+        self.current_segment.clear();
+        self.segmenter.clear();
     }
 
-    fn process_character<F: Frame, C: ColModify>(
+    fn process_character<S: WrapSink, C: ColModify>(
         &mut self,
         character: char,
+        index: usize,
         style: Style,
         context: ViewContext<C>,
-        frame: &mut F,
+        sink: &mut S,
     ) {
         if context.size.width() == 0 {
             return;
         }
         match character {
             '\n' => {
-                self.flush(context, frame);
-                self.cursor.x = 0;
-                self.cursor.y += 1;
+                self.flush_segment(context, sink);
+                self.line
+                    .draw_and_advance(self.alignment, true, context, sink);
+                self.line.indent = 0;
+                self.segmenter.clear();
             }
             '\r' => {
-                self.flush(context, frame);
-                self.cursor.x = 0;
-            }
-            ' ' => {
-                self.flush(context, frame);
-                if self.cursor.x != 0 {
-                    let view_cell = ViewCell {
-                        character: Some(' '),
-                        style,
-                    };
-                    frame.set_cell_relative(self.cursor, 0, view_cell, context);
-                    self.cursor.x += 1;
-                    assert!(self.cursor.x as u32 <= context.size.width());
-                    if self.cursor.x as u32 == context.size.width() {
-                        self.cursor.x = 0;
-                        self.cursor.y += 1;
+                self.flush_segment(context, sink);
+                self.line.draw(self.alignment, true, context, sink);
+                self.segmenter.clear();
+            }
+            other => match self.segmenter.segment(other) {
+                Segment::Join => (),
+                Segment::Tab { break_before } => {
+                    if break_before {
+                        self.flush_segment(context, sink);
+                    }
+                    let position = self.line.width + self.current_segment_width();
+                    for _ in 0..tab_stop_spaces(position, self.tab_width) {
+                        self.push(WrapCell::space(index, style), context, sink);
                     }
                 }
-            }
-            other => {
-                let view_cell = ViewCell {
-                    character: Some(other),
-                    style,
-                };
-                self.current_word_buffer.push(view_cell);
-                assert!(
-                    self.cursor.x as u32 + self.current_word_buffer.len() as u32
-                        <= context.size.width()
-                );
-                if self.cursor.x as u32 + self.current_word_buffer.len() as u32
-                    == context.size.width()
-                {
-                    if self.cursor.x == 0 {
-                        self.flush(context, frame);
-                    } else {
-                        self.cursor.x = 0;
-                        self.cursor.y += 1;
+                Segment::Cluster {
+                    width,
+                    break_before,
+                } => {
+                    if break_before {
+                        self.flush_segment(context, sink);
                     }
+                    self.push(
+                        WrapCell::character(other, index, width, style),
+                        context,
+                        sink,
+                    );
                 }
+            },
+        }
+    }
+
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.flush_segment(context, sink);
+        self.line.draw(self.alignment, true, context, sink);
+    }
+
+    fn num_lines(&self) -> usize {
+        self.line.y as usize + 1
+    }
+}
+
+impl Char {
+    fn place<S: WrapSink, C: ColModify>(
+        &mut self,
+        wrap_cell: WrapCell,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        if self.line.width != 0 && wrap_cell.width > self.line.remaining(context) {
+            self.line
+                .draw_and_advance(self.alignment, false, context, sink);
+        }
+        self.line.push(wrap_cell);
+    }
+}
+
+impl Wrap for Char {
+    fn clear(&mut self) {
+        self.line.clear();
+        self.segmenter.clear();
+    }
+
+    fn process_character<S: WrapSink, C: ColModify>(
+        &mut self,
+        character: char,
+        index: usize,
+        style: Style,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        match character {
+            '\n' => {
+                self.line
+                    .draw_and_advance(self.alignment, true, context, sink);
+                self.segmenter.clear();
+            }
+            '\r' => {
+                self.line.draw(self.alignment, true, context, sink);
+                self.segmenter.clear();
             }
+            other => match self.segmenter.segment(other) {
+                Segment::Join => (),
+                Segment::Tab { .. } => {
+                    for _ in 0..tab_stop_spaces(self.line.width, self.tab_width) {
+                        self.place(WrapCell::space(index, style), context, sink);
+                    }
+                }
+                Segment::Cluster { width, .. } => {
+                    self.place(
+                        WrapCell::character(other, index, width, style),
+                        context,
+                        sink,
+                    );
+                }
+            },
         }
     }
 
-    fn flush<F: Frame, C: ColModify>(&mut self, context: ViewContext<C>, frame: &mut F) {
-        if context.size.width() == 0 {
-            self.current_word_buffer.clear();
-            return;
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.line.draw(self.alignment, true, context, sink);
+    }
+
+    fn num_lines(&self) -> usize {
+        self.line.y as usize + 1
+    }
+}
+
+/// Word wrapping in which every line of a paragraph after the first is indented
+/// by `indent` cells, such as for the items of a bulleted list
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct HangingIndent {
+    word: Word,
+}
+
+impl HangingIndent {
+    pub fn new(indent: u32) -> Self {
+        Self {
+            word: Word {
+                hanging_indent: indent,
+                ..Word::new()
+            },
         }
-        for view_cell in self.current_word_buffer.drain(..) {
-            frame.set_cell_relative(self.cursor, 0, view_cell, context);
-            self.cursor.x += 1;
+    }
+    pub fn with_tab_width(self, tab_width: u32) -> Self {
+        Self {
+            word: self.word.with_tab_width(tab_width),
         }
-        assert!(self.cursor.x as u32 <= context.size.width());
-        if self.cursor.x as u32 == context.size.width() {
-            self.cursor.x = 0;
-            self.cursor.y += 1;
+    }
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self {
+            word: self.word.with_alignment(alignment),
         }
     }
+}
 
+impl Wrap for HangingIndent {
+    fn clear(&mut self) {
+        self.word.clear();
+    }
+    fn process_character<S: WrapSink, C: ColModify>(
+        &mut self,
+        character: char,
+        index: usize,
+        style: Style,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        self.word
+            .process_character(character, index, style, context, sink);
+    }
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.word.flush(context, sink);
+    }
     fn num_lines(&self) -> usize {
-        self.cursor.y as usize + 1
+        self.word.num_lines()
     }
 }
 
-impl Wrap for Char {
-    fn clear(&mut self) {
-        self.cursor = Coord::new(0, 0);
+/// Where `Ellipsis` removes text from lines which are too wide
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EllipsisPosition {
+    Start,
+    Middle,
+    #[default]
+    End,
+}
+
+/// Doesn't break lines. Lines which are too wide for the context are cut short,
+/// with an ellipsis character in place of the removed text. By default text is
+/// removed from the end of the line.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Ellipsis {
+    line: Line,
+    tab_width: u32,
+    alignment: TextAlignment,
+    ellipsis: char,
+    position: EllipsisPosition,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    segmenter: Segmenter,
+}
+
+impl Ellipsis {
+    pub fn new() -> Self {
+        Self {
+            line: Line::new(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            alignment: TextAlignment::Left,
+            ellipsis: '…',
+            position: EllipsisPosition::End,
+            segmenter: Segmenter::default(),
+        }
     }
+    pub fn with_tab_width(self, tab_width: u32) -> Self {
+        Self { tab_width, ..self }
+    }
+    pub fn with_alignment(self, alignment: TextAlignment) -> Self {
+        Self { alignment, ..self }
+    }
+    pub fn with_ellipsis(self, ellipsis: char) -> Self {
+        Self { ellipsis, ..self }
+    }
+    pub fn with_position(self, position: EllipsisPosition) -> Self {
+        Self { position, ..self }
+    }
+    fn draw_line<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.line
+            .truncate(context.size.width(), self.ellipsis, self.position);
+        self.line.draw(self.alignment, true, context, sink);
+    }
+}
 
-    fn process_character<F: Frame, C: ColModify>(
+impl Default for Ellipsis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Wrap for Ellipsis {
+    fn clear(&mut self) {
+        self.line.clear();
+        self.segmenter.clear();
+    }
+    fn process_character<S: WrapSink, C: ColModify>(
         &mut self,
         character: char,
+        index: usize,
         style: Style,
         context: ViewContext<C>,
-        frame: &mut F,
+        sink: &mut S,
     ) {
         match character {
             '\n' => {
-                self.cursor.x = 0;
-                self.cursor.y += 1;
+                self.draw_line(context, sink);
+                self.line.y += 1;
+                self.segmenter.clear();
             }
-            '\r' => self.cursor.x = 0,
-            other => {
-                let view_cell = ViewCell {
-                    character: Some(other),
-                    style,
-                };
-                frame.set_cell_relative(self.cursor, 0, view_cell, context);
-                self.cursor += Coord::new(1, 0);
-                if self.cursor.x >= context.size.width() as i32 {
-                    self.cursor.x = 0;
-                    self.cursor.y += 1;
-                }
+            '\r' => {
+                self.draw_line(context, sink);
+                self.segmenter.clear();
             }
+            other => match self.segmenter.segment(other) {
+                Segment::Join => (),
+                Segment::Tab { .. } => {
+                    for _ in 0..tab_stop_spaces(self.line.width, self.tab_width) {
+                        self.line.push(WrapCell::space(index, style));
+                    }
+                }
+                Segment::Cluster { width, .. } => {
+                    self.line
+                        .push(WrapCell::character(other, index, width, style));
+                }
+            },
         }
     }
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.draw_line(context, sink);
+    }
+    fn num_lines(&self) -> usize {
+        self.line.y as usize + 1
+    }
+}
+
+/// Doesn't break lines. Lines which are too wide for the context scroll to the
+/// left over time, wrapping around to their start. Lines which fit are drawn
+/// left-aligned and don't move.
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Marquee {
+    line: Line,
+    tab_width: u32,
+    state: MarqueeState,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    segmenter: Segmenter,
+}
 
+impl Marquee {
+    pub fn new(state: MarqueeState) -> Self {
+        Self {
+            line: Line::new(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            state,
+            segmenter: Segmenter::default(),
+        }
+    }
+    pub fn with_tab_width(self, tab_width: u32) -> Self {
+        Self { tab_width, ..self }
+    }
+    fn draw_line<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        if self.line.width <= context.size.width() {
+            self.line.draw(TextAlignment::Left, true, context, sink);
+        } else {
+            let gap = self.state.gap();
+            let scroll = self.state.scroll(self.line.width + gap);
+            self.line.draw_scrolled(scroll, gap, context, sink);
+        }
+    }
+}
+
+impl Wrap for Marquee {
+    fn clear(&mut self) {
+        self.line.clear();
+        self.segmenter.clear();
+    }
+    fn process_character<S: WrapSink, C: ColModify>(
+        &mut self,
+        character: char,
+        index: usize,
+        style: Style,
+        context: ViewContext<C>,
+        sink: &mut S,
+    ) {
+        match character {
+            '\n' => {
+                self.draw_line(context, sink);
+                self.line.y += 1;
+                self.segmenter.clear();
+            }
+            '\r' => {
+                self.draw_line(context, sink);
+                self.segmenter.clear();
+            }
+            other => match self.segmenter.segment(other) {
+                Segment::Join => (),
+                Segment::Tab { .. } => {
+                    for _ in 0..tab_stop_spaces(self.line.width, self.tab_width) {
+                        self.line.push(WrapCell::space(index, style));
+                    }
+                }
+                Segment::Cluster { width, .. } => {
+                    self.line
+                        .push(WrapCell::character(other, index, width, style));
+                }
+            },
+        }
+    }
+    fn flush<S: WrapSink, C: ColModify>(&mut self, context: ViewContext<C>, sink: &mut S) {
+        self.draw_line(context, sink);
+    }
     fn num_lines(&self) -> usize {
-        self.cursor.y as usize + 1
+        self.line.y as usize + 1
     }
 }
 
-mod private_wrap {
-    pub trait Sealed {}
-    impl Sealed for super::None {}
-    impl Sealed for super::Word {}
-    impl Sealed for super::Char {}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::StringView;
+    use chargrid_test_grid::TestGrid;
+    use std::time::Duration;
+
+    fn render<W: Wrap>(wrap: W, text: &str, size: Size) -> Vec<String> {
+        let mut test_grid = TestGrid::new(size);
+        let context = ViewContext::default_with_size(size);
+        StringView::new_default_style(wrap).view(text, context, &mut test_grid);
+        test_grid.string_rows()
+    }
+
+    #[test]
+    fn word_wrap_wide_characters() {
+        assert_eq!(
+            render(Word::new(), "ab 日本語", Size::new(6, 2)),
+            &["ab 日  ", "本 語   "]
+        );
+    }
+
+    #[test]
+    fn wide_characters_are_clipped() {
+        assert_eq!(render(None::new(), "ab漢c", Size::new(3, 1)), &["ab "]);
+        assert_eq!(render(None::new(), "漢c", Size::new(1, 1)), &[" "]);
+        assert_eq!(render(Word::new(), "漢字", Size::new(1, 2)), &[" ", " "]);
+    }
+
+    #[test]
+    fn word_wrap_non_breaking_space() {
+        assert_eq!(
+            render(Word::new(), "a b\u{A0}c", Size::new(4, 2)),
+            &["a   ", "b c "]
+        );
+    }
+
+    #[test]
+    fn tab_stops() {
+        assert_eq!(
+            render(None::new().with_tab_width(4), "a\tb\n\tc", Size::new(6, 2)),
+            &["a   b ", "    c "]
+        );
+    }
+
+    #[test]
+    fn alignment() {
+        let text = "aa bb cc dd\nee ff";
+        assert_eq!(
+            render(
+                Word::new().with_alignment(TextAlignment::Centre),
+                text,
+                Size::new(9, 3)
+            ),
+            &["aa bb cc ", "   dd    ", "  ee ff  "]
+        );
+        assert_eq!(
+            render(
+                Word::new().with_alignment(TextAlignment::Right),
+                text,
+                Size::new(9, 3)
+            ),
+            &[" aa bb cc", "       dd", "    ee ff"]
+        );
+        assert_eq!(
+            render(
+                Word::new().with_alignment(TextAlignment::Justify),
+                text,
+                Size::new(10, 3)
+            ),
+            &["aa  bb  cc", "dd        ", "ee ff     "]
+        );
+    }
+
+    #[test]
+    fn hanging_indent() {
+        assert_eq!(
+            render(
+                HangingIndent::new(2),
+                "- aaa bbb ccc\n- dd",
+                Size::new(8, 4)
+            ),
+            &["- aaa   ", "  bbb   ", "  ccc   ", "- dd    "]
+        );
+    }
+
+    #[test]
+    fn ellipsis() {
+        assert_eq!(
+            render(Ellipsis::new(), "hello world\nhi", Size::new(8, 2)),
+            &["hello w…", "hi      "]
+        );
+    }
+
+    #[test]
+    fn ellipsis_positions() {
+        let render_position = |position| {
+            render(
+                Ellipsis::new().with_position(position),
+                "hello world",
+                Size::new(8, 1),
+            )
+        };
+        assert_eq!(render_position(EllipsisPosition::Start), &["…o world"]);
+        assert_eq!(render_position(EllipsisPosition::Middle), &["hell…rld"]);
+        assert_eq!(render_position(EllipsisPosition::End), &["hello w…"]);
+    }
+
+    #[test]
+    fn marquee() {
+        let state = MarqueeState::new()
+            .with_step(Duration::from_millis(100))
+            .with_pause(Duration::from_millis(0))
+            .with_gap(2);
+        let render_after = |millis| {
+            let mut state = state;
+            state.on_frame(Duration::from_millis(millis));
+            render(Marquee::new(state), "abcdef", Size::new(4, 1))
+        };
+        assert_eq!(render_after(0), &["abcd"]);
+        assert_eq!(render_after(300), &["def "]);
+        assert_eq!(render_after(500), &["f  a"]);
+        assert_eq!(render_after(800), &["abcd"]);
+        assert_eq!(
+            render(Marquee::new(state), "ab", Size::new(4, 1)),
+            &["ab  "]
+        );
+    }
+
+    #[test]
+    fn long_word_is_broken() {
+        assert_eq!(
+            render(Word::new(), "x abcdefgh", Size::new(5, 3)),
+            &["x    ", "abcde", "fgh  "]
+        );
+    }
 }
@@ -2,27 +2,27 @@ use chargrid_input::*;
 
 macro_rules! convert_char_shift {
     ($lower:expr, $upper:expr, $shift:expr) => {
-        KeyboardInput::Char(if $shift { $upper } else { $lower })
+        Key::Char(if $shift { $upper } else { $lower })
     };
 }
 
 #[allow(clippy::cognitive_complexity)]
-fn keyboard_input_from_js_event_key_press(key_code: u8, shift: bool) -> Option<KeyboardInput> {
-    let keyboard_input = match key_code {
-        8 => keys::BACKSPACE,
-        9 => keys::TAB,
-        13 => keys::RETURN,
-        27 => keys::ESCAPE,
-        32 => KeyboardInput::Char(' '),
-        33 => KeyboardInput::PageUp,
-        34 => KeyboardInput::PageDown,
-        35 => KeyboardInput::End,
-        36 => KeyboardInput::Home,
-        37 => KeyboardInput::Left,
-        38 => KeyboardInput::Up,
-        39 => KeyboardInput::Right,
-        40 => KeyboardInput::Down,
-        46 => KeyboardInput::Delete,
+fn key_from_js_event_key_press(key_code: u8, shift: bool) -> Option<Key> {
+    let key = match key_code {
+        8 => keys::BACKSPACE.key,
+        9 => keys::TAB.key,
+        13 => keys::RETURN.key,
+        27 => keys::ESCAPE.key,
+        32 => Key::Char(' '),
+        33 => Key::PageUp,
+        34 => Key::PageDown,
+        35 => Key::End,
+        36 => Key::Home,
+        37 => Key::Left,
+        38 => Key::Up,
+        39 => Key::Right,
+        40 => Key::Down,
+        46 => Key::Delete,
         48 => convert_char_shift!('0', ')', shift),
         49 => convert_char_shift!('1', '!', shift),
         50 => convert_char_shift!('2', '@', shift),
@@ -59,33 +59,33 @@ fn keyboard_input_from_js_event_key_press(key_code: u8, shift: bool) -> Option<K
         88 => convert_char_shift!('x', 'X', shift),
         89 => convert_char_shift!('y', 'Y', shift),
         90 => convert_char_shift!('z', 'Z', shift),
-        96 => KeyboardInput::Char('0'),
-        97 => KeyboardInput::Char('1'),
-        98 => KeyboardInput::Char('2'),
-        99 => KeyboardInput::Char('3'),
-        100 => KeyboardInput::Char('4'),
-        101 => KeyboardInput::Char('5'),
-        102 => KeyboardInput::Char('6'),
-        103 => KeyboardInput::Char('7'),
-        104 => KeyboardInput::Char('8'),
-        105 => KeyboardInput::Char('9'),
-        106 => KeyboardInput::Char('*'),
-        107 => KeyboardInput::Char('+'),
-        109 => KeyboardInput::Char('-'),
-        110 => KeyboardInput::Char('.'),
-        111 => KeyboardInput::Char('/'),
-        112 => KeyboardInput::Function(1),
-        113 => KeyboardInput::Function(2),
-        114 => KeyboardInput::Function(3),
-        115 => KeyboardInput::Function(4),
-        116 => KeyboardInput::Function(5),
-        117 => KeyboardInput::Function(6),
-        118 => KeyboardInput::Function(7),
-        119 => KeyboardInput::Function(8),
-        120 => KeyboardInput::Function(9),
-        121 => KeyboardInput::Function(10),
-        122 => KeyboardInput::Function(11),
-        123 => KeyboardInput::Function(12),
+        96 => Key::Char('0'),
+        97 => Key::Char('1'),
+        98 => Key::Char('2'),
+        99 => Key::Char('3'),
+        100 => Key::Char('4'),
+        101 => Key::Char('5'),
+        102 => Key::Char('6'),
+        103 => Key::Char('7'),
+        104 => Key::Char('8'),
+        105 => Key::Char('9'),
+        106 => Key::Char('*'),
+        107 => Key::Char('+'),
+        109 => Key::Char('-'),
+        110 => Key::Char('.'),
+        111 => Key::Char('/'),
+        112 => Key::Function(1),
+        113 => Key::Function(2),
+        114 => Key::Function(3),
+        115 => Key::Function(4),
+        116 => Key::Function(5),
+        117 => Key::Function(6),
+        118 => Key::Function(7),
+        119 => Key::Function(8),
+        120 => Key::Function(9),
+        121 => Key::Function(10),
+        122 => Key::Function(11),
+        123 => Key::Function(12),
         186 => convert_char_shift!(';', ':', shift),
         187 => convert_char_shift!('=', '+', shift),
         188 => convert_char_shift!(',', '<', shift),
@@ -99,9 +99,16 @@ fn keyboard_input_from_js_event_key_press(key_code: u8, shift: bool) -> Option<K
         222 => convert_char_shift!('\'', '"', shift),
         _ => return None,
     };
-    Some(keyboard_input)
+    Some(key)
 }
 
-pub fn from_js_event_key_press(key_code: u8, shift: bool) -> Option<Input> {
-    keyboard_input_from_js_event_key_press(key_code, shift).map(Input::Keyboard)
+pub fn from_js_event_key_press(key_code: u8, modifiers: KeyModifiers) -> Option<Input> {
+    key_from_js_event_key_press(key_code, modifiers.contains(KeyModifiers::SHIFT)).map(|key| {
+        let modifiers = if key.is_printable() {
+            modifiers.difference(KeyModifiers::SHIFT)
+        } else {
+            modifiers
+        };
+        Input::Keyboard(KeyboardInput::new(key).with_modifiers(modifiers))
+    })
 }
@@ -5,7 +5,7 @@ use chargrid_app::App;
 use chargrid_gamepad::GamepadContext;
 pub use chargrid_input;
 pub use chargrid_input::{Input, MouseInput};
-use chargrid_input::{MouseButton, ScrollDirection};
+use chargrid_input::{KeyModifiers, MouseButton, ScrollDirection};
 pub use chargrid_render;
 use chargrid_render::{Buffer, Rgb24, ViewContext};
 use grid_2d::Coord;
@@ -273,10 +273,22 @@ fn run_app_input<A: App + 'static>(app: Rc<RefCell<A>>, context: Rc<RefCell<Cont
         let app = app.clone();
         Closure::wrap(Box::new(move |event: JsValue| {
             let keyboard_event = event.unchecked_ref::<KeyboardEvent>();
-            if let Some(input) = input::from_js_event_key_press(
-                keyboard_event.key_code() as u8,
-                keyboard_event.shift_key(),
-            ) {
+            let mut modifiers = KeyModifiers::NONE;
+            if keyboard_event.shift_key() {
+                modifiers |= KeyModifiers::SHIFT;
+            }
+            if keyboard_event.ctrl_key() {
+                modifiers |= KeyModifiers::CTRL;
+            }
+            if keyboard_event.alt_key() {
+                modifiers |= KeyModifiers::ALT;
+            }
+            if keyboard_event.meta_key() {
+                modifiers |= KeyModifiers::SUPER;
+            }
+            if let Some(input) =
+                input::from_js_event_key_press(keyboard_event.key_code() as u8, modifiers)
+            {
                 app.borrow_mut().on_input(input);
             }
         }) as Box<dyn FnMut(JsValue)>)